@@ -1,14 +1,41 @@
 pub const DEB_DENOMINATION: u64 = 100_000;
 pub const VESTA_CODING_DIVISION_FULL_SET_MAX_NONCE: u64 = 10;
+pub const MAX_PERCENTAGE: u64 = 10_000;
+pub const REWARD_PER_SHARE_PRECISION: u64 = 1_000_000_000_000_000_000;
 
 pub const DEFAULT_UNBONDING_TIME_PENALTY: u64 = 3 * 24 * 3600; // three days
+pub const MAX_LEGACY_SETTLEMENT_EPOCHS: u64 = 100;
 
 // error messages
 pub const ERR_FAILED_UNBONDING: &str = "Unbonding failed";
-pub const ERR_ONE_TOKEN_ID_SUPPORTED: &str = "Only one token id is allowed per TX";
+pub const ERR_NOTHING_TO_STAKE: &str = "Nothing to stake";
+pub const ERR_NOTHING_TO_UNSTAKE: &str = "Nothing to unstake";
 pub const ERR_NOTHING_TO_CLAIM: &str = "Nothing to claim";
+pub const ERR_NOTHING_TO_DISTRIBUTE: &str = "Nothing to distribute";
 pub const ERR_REWARD_ALREADY_DISTRIBUTED: &str = "Reward already distributed";
 pub const ERR_INVALID_REWARD_TOKEN_ID: &str = "Invalid reward token id";
+pub const ERR_INVALID_REWARD_TOKEN_NONCE: &str = "Invalid reward token nonce";
+pub const ERR_INVALID_REWARD_TARGET: &str = "Reward token not registered for staking module";
+pub const ERR_REWARD_TOKEN_RETIRED: &str = "Reward token is retired";
+pub const ERR_REWARD_TOKEN_NOT_RETIRED: &str = "Reward token is not retired";
+pub const ERR_INVALID_SWEEP_DEADLINE: &str = "Invalid sweep deadline";
+pub const ERR_REWARDS_NOT_CLAIMED: &str = "Rewards are still being claimed";
+pub const ERR_LEGACY_REWARDS_NOT_SETTLED: &str = "Legacy rewards must be settled first";
 pub const ERR_COLLECTION_ALREADY_REGISTERED: &str = "Collection already registered";
 pub const ERR_INVALID_STAKED_TOKEN_ID: &str = "Invalid token identifier";
 pub const ERR_CANNOT_REGISTER_AS_ALL: &str = "Cannot register as Module Type::All";
+pub const ERR_INVALID_UNBONDING_BATCH: &str = "Invalid unbonding batch";
+pub const ERR_INSTANT_UNSTAKE_DISABLED: &str = "Instant unstake is disabled for this pool";
+pub const ERR_INVALID_FEE_PAYMENT: &str = "Invalid fee payment";
+pub const ERR_INVALID_FEE_CONFIGURATION: &str = "Invalid fee configuration";
+pub const ERR_TREASURY_NOT_SET: &str = "Treasury address not set";
+pub const ERR_INVALID_REWARD_SCHEDULE: &str = "Invalid reward schedule";
+pub const ERR_INVALID_DISTRIBUTION_WEIGHTS: &str = "Invalid distribution weights";
+pub const ERR_INVALID_REWARD_RECEIVER: &str = "Invalid reward receiver";
+pub const ERR_INVALID_VESTING_CONFIG: &str = "Invalid vesting configuration";
+pub const ERR_EARLY_WITHDRAW_DISABLED: &str = "Early withdrawal is disabled for this token";
+pub const ERR_CONTRACT_PAUSED: &str = "Contract is paused";
+pub const ERR_POOL_PAUSED: &str = "Pool is paused";
+pub const ERR_NOT_EMERGENCY_MODE: &str = "Emergency mode is not enabled";
+pub const ERR_EMERGENCY_MODE: &str = "Not allowed while emergency mode is enabled";
+pub const ERR_NOT_OWNER_OR_PAUSER: &str = "Only the owner or a pauser can call this endpoint";
@@ -0,0 +1,323 @@
+use crate::{
+    staking_modules::staking_module_type::StakingModuleType,
+    types::{
+        event_data::{
+            DebUpdateEventData, DistributionEventData, DistributionWeight, ForceUnstakeEventData,
+            StakingEventData,
+        },
+        instant_unstake_fee::{FeeDestination, InstantUnstakeFee},
+        reward_payment::RewardPayment,
+        vesting::VestingConfig,
+    },
+};
+
+multiversx_sc::imports!();
+
+#[multiversx_sc::module]
+pub trait EventsModule {
+    #[event("stake")]
+    fn stake_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] token_identifier: &TokenIdentifier,
+        data: &StakingEventData<Self::Api>,
+    );
+
+    #[event("startUnbonding")]
+    fn start_unbonding_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] token_identifier: &TokenIdentifier,
+        #[indexed] batch_id: u64,
+        data: &StakingEventData<Self::Api>,
+    );
+
+    #[event("instantUnstake")]
+    fn instant_unstake_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] token_identifier: &TokenIdentifier,
+        data: &StakingEventData<Self::Api>,
+    );
+
+    #[event("cancelUnbonding")]
+    fn cancel_unbonding_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] token_identifier: &TokenIdentifier,
+        #[indexed] batch_id: u64,
+        data: &StakingEventData<Self::Api>,
+    );
+
+    #[event("claimUnbonded")]
+    fn claim_unbonded_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        payments: &ManagedVec<EsdtTokenPayment>,
+    );
+
+    #[event("claimRewards")]
+    fn claim_rewards_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] receiver: &ManagedAddress,
+        rewards: &ManagedVec<RewardPayment<Self::Api>>,
+    );
+
+    #[event("vestRewards")]
+    fn vest_rewards_event(
+        &self,
+        #[indexed] user: &ManagedAddress,
+        rewards: &ManagedVec<RewardPayment<Self::Api>>,
+    );
+
+    #[event("withdrawVested")]
+    fn withdraw_vested_event(
+        &self,
+        #[indexed] user: &ManagedAddress,
+        #[indexed] receiver: &ManagedAddress,
+        rewards: &ManagedVec<RewardPayment<Self::Api>>,
+    );
+
+    #[event("withdrawVestedEarly")]
+    fn withdraw_vested_early_event(
+        &self,
+        #[indexed] user: &ManagedAddress,
+        #[indexed] receiver: &ManagedAddress,
+        #[indexed] penalty: &BigUint,
+        payment: &EgldOrEsdtTokenPayment,
+    );
+
+    #[event("setRewardReceiver")]
+    fn reward_receiver_event(&self, #[indexed] user: &ManagedAddress, receiver: &ManagedAddress);
+
+    #[event("emergencyWithdraw")]
+    fn emergency_withdraw_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        payments: &ManagedVec<EsdtTokenPayment>,
+    );
+
+    #[event("forceUnstake")]
+    fn force_unstake_event(
+        &self,
+        #[indexed] user: &ManagedAddress,
+        #[indexed] token_identifier: &TokenIdentifier,
+        data: &ForceUnstakeEventData<Self::Api>,
+    );
+
+    #[event("distributeReward")]
+    fn distribute_reward_event(
+        &self,
+        #[indexed] staking_module_type: &StakingModuleType,
+        #[indexed] epoch: u64,
+        data: &DistributionEventData<Self::Api>,
+    );
+
+    #[event("fundRewardSchedule")]
+    fn fund_reward_schedule_event(
+        &self,
+        #[indexed] staking_module_type: &StakingModuleType,
+        #[indexed] epochs: u64,
+        payment: &EgldOrEsdtTokenPayment,
+    );
+
+    #[event("depositRewardReservoir")]
+    fn deposit_reward_reservoir_event(
+        &self,
+        #[indexed] staking_module_type: &StakingModuleType,
+        payment: &EgldOrEsdtTokenPayment,
+    );
+
+    #[event("setVestingConfig")]
+    fn vesting_config_event(
+        &self,
+        #[indexed] reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        #[indexed] reward_token_nonce: u64,
+        config: &VestingConfig,
+    );
+
+    #[event("removeVestingConfig")]
+    fn remove_vesting_config_event(
+        &self,
+        #[indexed] reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        #[indexed] reward_token_nonce: u64,
+    );
+
+    #[event("setDistributionWeights")]
+    fn distribution_weights_event(
+        &self,
+        #[indexed] reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        #[indexed] reward_token_nonce: u64,
+        weights: &ManagedVec<DistributionWeight>,
+    );
+
+    #[event("withdrawRewardReservoir")]
+    fn withdraw_reward_reservoir_event(
+        &self,
+        #[indexed] staking_module_type: &StakingModuleType,
+        payment: &EgldOrEsdtTokenPayment,
+    );
+
+    #[event("setReservoirDistributionAmount")]
+    fn reservoir_distribution_amount_event(
+        &self,
+        #[indexed] staking_module_type: &StakingModuleType,
+        #[indexed] reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        #[indexed] reward_token_nonce: u64,
+        amount: &BigUint,
+    );
+
+    #[event("recoverZeroScoreReserve")]
+    fn recover_zero_score_reserve_event(
+        &self,
+        #[indexed] staking_module_type: &StakingModuleType,
+        payment: &EgldOrEsdtTokenPayment,
+    );
+
+    #[event("registerRewardToken")]
+    fn register_reward_token_event(
+        &self,
+        #[indexed] reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        #[indexed] reward_token_nonce: u64,
+        staking_module_type: &StakingModuleType,
+    );
+
+    #[event("retireRewardToken")]
+    fn retire_reward_token_event(
+        &self,
+        #[indexed] reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        #[indexed] reward_token_nonce: u64,
+        #[indexed] sweep_deadline: u64,
+        unclaimed_rewards: &BigUint,
+    );
+
+    #[event("sweepRewardToken")]
+    fn sweep_reward_token_event(
+        &self,
+        #[indexed] reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        payment: &EgldOrEsdtTokenPayment,
+    );
+
+    #[event("updateDeb")]
+    fn update_deb_event(
+        &self,
+        #[indexed] user: &ManagedAddress,
+        #[indexed] staking_module_type: &StakingModuleType,
+        data: &DebUpdateEventData<Self::Api>,
+    );
+
+    #[event("createPool")]
+    fn create_pool_event(
+        &self,
+        #[indexed] collection_token_identifier: &TokenIdentifier,
+        #[indexed] staking_module_type: &StakingModuleType,
+    );
+
+    #[event("overridePoolType")]
+    fn override_pool_type_event(
+        &self,
+        #[indexed] collection_token_identifier: &TokenIdentifier,
+        #[indexed] staking_module_type: &StakingModuleType,
+    );
+
+    #[event("setBaseAssetScore")]
+    fn base_asset_score_event(
+        &self,
+        #[indexed] collection_token_identifier: &TokenIdentifier,
+        #[indexed] staking_module_type: &StakingModuleType,
+        score: usize,
+    );
+
+    #[event("setNonceAssetScore")]
+    fn nonce_asset_score_event(
+        &self,
+        #[indexed] collection_token_identifier: &TokenIdentifier,
+        #[indexed] staking_module_type: &StakingModuleType,
+        #[indexed] score: usize,
+        nonces: &ManagedVec<u64>,
+    );
+
+    #[event("setNonceAssetScoreByRange")]
+    fn nonce_asset_score_by_range_event(
+        &self,
+        #[indexed] collection_token_identifier: &TokenIdentifier,
+        #[indexed] staking_module_type: &StakingModuleType,
+        #[indexed] nonce_range_start: u64,
+        #[indexed] nonce_range_end: u64,
+        score: usize,
+    );
+
+    #[event("setFullSetScore")]
+    fn full_set_score_event(
+        &self,
+        #[indexed] collection_token_identifier: &TokenIdentifier,
+        #[indexed] staking_module_type: &StakingModuleType,
+        score: usize,
+    );
+
+    #[event("setUnbondingTimePenalty")]
+    fn unbonding_time_penalty_event(&self, unbonding_time_penalty: u64);
+
+    #[event("setPoolUnbondingTimePenalty")]
+    fn pool_unbonding_time_penalty_event(
+        &self,
+        #[indexed] collection_token_identifier: &TokenIdentifier,
+        unbonding_time_penalty: u64,
+    );
+
+    #[event("removePoolUnbondingTimePenalty")]
+    fn remove_pool_unbonding_time_penalty_event(
+        &self,
+        #[indexed] collection_token_identifier: &TokenIdentifier,
+    );
+
+    #[event("setTreasuryAddress")]
+    fn treasury_address_event(&self, treasury_address: &ManagedAddress);
+
+    #[event("setInstantUnstakeFee")]
+    fn instant_unstake_fee_event(
+        &self,
+        #[indexed] collection_token_identifier: &TokenIdentifier,
+        #[indexed] destination: &FeeDestination,
+        fee: &InstantUnstakeFee<Self::Api>,
+    );
+
+    #[event("disableInstantUnstake")]
+    fn disable_instant_unstake_event(
+        &self,
+        #[indexed] collection_token_identifier: &TokenIdentifier,
+    );
+
+    #[event("addPauser")]
+    fn add_pauser_event(&self, #[indexed] address: &ManagedAddress);
+
+    #[event("removePauser")]
+    fn remove_pauser_event(&self, #[indexed] address: &ManagedAddress);
+
+    #[event("setClaimRewardsPausable")]
+    fn claim_rewards_pausable_event(&self, pausable: bool);
+
+    #[event("setEmergencyMode")]
+    fn emergency_mode_event(&self, enabled: bool);
+
+    #[event("pause")]
+    fn pause_event(&self, #[indexed] caller: &ManagedAddress);
+
+    #[event("unpause")]
+    fn unpause_event(&self, #[indexed] caller: &ManagedAddress);
+
+    #[event("pausePool")]
+    fn pause_pool_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] collection_token_identifier: &TokenIdentifier,
+    );
+
+    #[event("unpausePool")]
+    fn unpause_pool_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] collection_token_identifier: &TokenIdentifier,
+    );
+}
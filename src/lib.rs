@@ -1,25 +1,44 @@
 #![no_std]
 
-use constants::{DEFAULT_UNBONDING_TIME_PENALTY, ERR_FAILED_UNBONDING, ERR_ONE_TOKEN_ID_SUPPORTED};
+use constants::{
+    DEFAULT_UNBONDING_TIME_PENALTY, ERR_FAILED_UNBONDING, ERR_INSTANT_UNSTAKE_DISABLED,
+    ERR_INVALID_FEE_PAYMENT, ERR_INVALID_REWARD_RECEIVER, ERR_INVALID_UNBONDING_BATCH,
+    ERR_NOTHING_TO_DISTRIBUTE, ERR_NOTHING_TO_STAKE, ERR_NOTHING_TO_UNSTAKE,
+    ERR_NOT_EMERGENCY_MODE, ERR_REWARD_ALREADY_DISTRIBUTED, REWARD_PER_SHARE_PRECISION,
+};
 use staking_context::StakingContext;
-use types::{nonce_qty_pair::NonceQtyPair, start_unbonding_payload::StartUnbondingPayload};
-use utils::get_all_pending_rewards;
+use staking_modules::staking_module_type::StakingModuleType;
+use types::{
+    instant_unstake_fee::{FeeDestination, InstantUnstakeFee},
+    nonce_qty_pair::NonceQtyPair,
+    reward_payment::RewardPayment,
+    reward_token::RewardToken,
+    start_unbonding_payload::StartUnbondingPayload,
+};
+use utils::{
+    get_all_pending_rewards, is_user_reward_stale, migrate_legacy_reward_tokens,
+    migrate_legacy_unbonding_assets, saturating_sub, settle_user_legacy_rewards,
+    update_unsettled_score,
+};
 
 use crate::{
     constants::{ERR_INVALID_STAKED_TOKEN_ID, ERR_NOTHING_TO_CLAIM},
-    utils::claim_all_pending_rewards,
+    utils::{claim_all_pending_rewards, claim_token_pending_rewards, send_reward_payments},
 };
 
 multiversx_sc::imports!();
 
 pub mod constants;
+pub mod events;
 pub mod owner;
+pub mod pause;
 pub mod requirements;
 pub mod staking_context;
 pub mod staking_modules;
 pub mod storage;
 pub mod types;
 pub mod utils;
+pub mod vesting;
 pub mod views;
 
 #[multiversx_sc::contract]
@@ -27,85 +46,530 @@ pub trait NftStakingContract:
     storage::config::ConfigModule
     + storage::score::ScoreStorageModule
     + storage::user_data::UserDataStorageModule
+    + storage::pause::PauseStorageModule
     + owner::OwnerModule
+    + pause::PauseModule
     + views::ViewsModule
+    + vesting::VestingModule
     + requirements::RequirementsModule
+    + events::EventsModule
 {
+    /// `legacy_reward_targets` lists the (token, staking module) pairs rewarded through the
+    /// per-epoch `reward_rate` when upgrading from that model, empty otherwise.
     #[init]
-    fn init(&self, primary_reward_token_identifier: TokenIdentifier) {
+    fn init(
+        &self,
+        primary_reward_token_identifier: EgldOrEsdtTokenIdentifier,
+        legacy_reward_targets: MultiValueEncoded<
+            MultiValue2<EgldOrEsdtTokenIdentifier, StakingModuleType>,
+        >,
+    ) {
+        // upgrading from the per-epoch reward model, older epochs are settled through `reward_rate`
+        if !self.primary_reward_token_identifier().is_empty()
+            && self.legacy_reward_epoch_end().is_empty()
+        {
+            self.legacy_reward_epoch_end()
+                .set(self.blockchain().get_block_epoch());
+            self.legacy_reward_tokens()
+                .insert(self.primary_reward_token_identifier().get());
+        }
+
         self.unbonding_time_penalty()
             .set_if_empty(&DEFAULT_UNBONDING_TIME_PENALTY);
         self.primary_reward_token_identifier()
             .set_if_empty(&primary_reward_token_identifier);
-        self.reward_token_identifiers()
-            .insert(primary_reward_token_identifier);
+
+        // the primary token was never mapped before the registry, it always targets `All`
+        migrate_legacy_reward_tokens(self, legacy_reward_targets);
+        let primary_reward_token_identifier = self.primary_reward_token_identifier().get();
+        let reward_token_key = (primary_reward_token_identifier.clone(), 0);
+        let mut reward_token = match self.reward_registry().get(&reward_token_key) {
+            Some(reward_token) => reward_token,
+            None => RewardToken::new(
+                primary_reward_token_identifier.clone(),
+                0,
+                REWARD_PER_SHARE_PRECISION,
+                self.reward_token_generation(&primary_reward_token_identifier, 0)
+                    .get(),
+            ),
+        };
+        if !reward_token.targets(&StakingModuleType::All) {
+            reward_token.staking_modules.push(StakingModuleType::All);
+            self.reward_registry()
+                .insert(reward_token_key, reward_token);
+        }
     }
 
     #[payable("*")]
     #[endpoint(stake)]
     fn stake(&self) {
+        self.require_not_emergency_mode();
         let payments = self.call_value().all_esdt_transfers();
-        self.require_same_token_id(&payments);
-        let mut context = StakingContext::new(self, &payments.get(0).token_identifier);
-        context.add_to_stake(&payments);
-        context.update_staking_scores();
+        require!(!payments.is_empty(), ERR_NOTHING_TO_STAKE);
+
+        // rewards of a staking module are secured once, before its first score change
+        let caller = self.blockchain().get_caller();
+        let mut secured_modules = ManagedVec::new();
+        for token_payments in self.group_payments_by_token_id(&payments).iter() {
+            let token_identifier = token_payments.get(0).token_identifier;
+            self.require_pool_not_paused(&token_identifier);
+            let mut context = StakingContext::new(self, &token_identifier);
+            context.add_to_stake_with_secured_modules(&token_payments, &mut secured_modules);
+            context.update_staking_scores();
+
+            let items = token_payments
+                .iter()
+                .map(|payment| NonceQtyPair {
+                    nonce: payment.token_nonce,
+                    quantity: payment.amount,
+                })
+                .collect();
+            self.stake_event(&caller, &token_identifier, &context.get_event_data(items));
+        }
     }
 
     #[endpoint(startUnbonding)]
     fn start_unbonding(&self, payload: StartUnbondingPayload<Self::Api>) {
         self.require_unbonding_is_valid(&payload);
+        self.start_unbonding_payload(payload, &mut ManagedVec::new());
+    }
+
+    #[endpoint(startUnbondingMultiple)]
+    fn start_unbonding_multiple(
+        &self,
+        payloads: MultiValueEncoded<StartUnbondingPayload<Self::Api>>,
+    ) {
+        require!(!payloads.is_empty(), ERR_FAILED_UNBONDING);
+
+        let mut secured_modules = ManagedVec::new();
+        for payload in payloads.into_iter() {
+            self.require_unbonding_is_valid(&payload);
+            self.start_unbonding_payload(payload, &mut secured_modules);
+        }
+    }
+
+    #[endpoint(unstakeAll)]
+    fn unstake_all(&self) {
+        let caller = self.blockchain().get_caller();
+
+        let mut secured_modules = ManagedVec::new();
+        let mut has_staked_assets = false;
+        for token_identifier in self.eligible_stake_token_identifiers().iter() {
+            let staked_nfts = self.get_staked_nfts(&caller, &token_identifier);
+            if staked_nfts.is_empty() {
+                continue;
+            }
+
+            let payload = StartUnbondingPayload::new(token_identifier, staked_nfts);
+            self.start_unbonding_payload(payload, &mut secured_modules);
+            has_staked_assets = true;
+        }
+
+        require!(has_staked_assets, ERR_NOTHING_TO_UNSTAKE);
+    }
+
+    #[payable("*")]
+    #[endpoint(instantUnstake)]
+    fn instant_unstake(&self, payload: StartUnbondingPayload<Self::Api>) {
+        self.require_unbonding_is_valid(&payload);
+        self.require_pool_not_paused(&payload.token_identifier);
+        require!(
+            !self
+                .instant_unstake_fee(&payload.token_identifier)
+                .is_empty(),
+            ERR_INSTANT_UNSTAKE_DISABLED
+        );
 
         let mut context = StakingContext::new(self, &payload.token_identifier);
-        let is_unbonding_successful = context.start_unbonding(payload);
-        require!(is_unbonding_successful, ERR_FAILED_UNBONDING);
+        let is_unstake_successful = context.unstake(payload.clone());
+        require!(is_unstake_successful, ERR_FAILED_UNBONDING);
+        context.update_staking_scores();
+
+        let caller = self.blockchain().get_caller();
+        self.instant_unstake_event(
+            &caller,
+            &payload.token_identifier,
+            &context.get_event_data(payload.items.clone()),
+        );
+        self.collect_instant_unstake_fee(&payload.token_identifier);
+
+        self.send().direct_multi(&caller, &payload.get_payments());
+    }
+
+    #[endpoint(cancelUnbonding)]
+    fn cancel_unbonding(&self, batch_id: u64) {
+        self.require_not_emergency_mode();
+        let caller = self.blockchain().get_caller();
+        migrate_legacy_unbonding_assets(self, &caller);
+
+        let unbonding_batch = self.unbonding_batches(&caller).remove(&batch_id);
+        require!(unbonding_batch.is_some(), ERR_INVALID_UNBONDING_BATCH);
+
+        let unbonding_payload = unbonding_batch.unwrap().payload;
+        self.require_pool_not_paused(&unbonding_payload.token_identifier);
+        let mut context = StakingContext::new(self, &unbonding_payload.token_identifier);
+        context.add_to_stake(&unbonding_payload.get_payments());
         context.update_staking_scores();
+
+        self.cancel_unbonding_event(
+            &caller,
+            &unbonding_payload.token_identifier,
+            batch_id,
+            &context.get_event_data(unbonding_payload.items.clone()),
+        );
     }
 
     #[endpoint(claimUnbonded)]
     fn claim_unbonded(&self) {
         let caller = self.blockchain().get_caller();
         let block_timestamp = self.blockchain().get_block_timestamp();
-        let unbonding_time_penalty = self.unbonding_time_penalty().get();
+        migrate_legacy_unbonding_assets(self, &caller);
 
+        let mut unbonded_batch_ids = ManagedVec::<Self::Api, u64>::new();
         let mut payments = ManagedVec::new();
-        for unbonding_batch in self.unbonding_assets(&caller).iter() {
-            let (start_unbonding_timestamp, unbonding_payload) = unbonding_batch;
-            if start_unbonding_timestamp + unbonding_time_penalty > block_timestamp {
+        for (batch_id, unbonding_batch) in self.unbonding_batches(&caller).iter() {
+            if !unbonding_batch.is_unbonded(block_timestamp) {
                 continue;
             }
 
-            let batch_payments = unbonding_payload.get_payments();
+            let batch_payments = unbonding_batch.payload.get_payments();
             payments.extend(&batch_payments);
-            self.unbonding_assets(&caller)
-                .remove(&start_unbonding_timestamp);
+            unbonded_batch_ids.push(batch_id);
         }
 
         require!(payments.len() > 0, ERR_NOTHING_TO_CLAIM);
 
+        for batch_id in unbonded_batch_ids.iter() {
+            self.unbonding_batches(&caller).remove(&batch_id);
+        }
+
         self.send().direct_multi(&caller, &payments);
+        self.claim_unbonded_event(&caller, &payments);
+    }
+
+    /// Returns every staked and unbonding asset of the caller without settling rewards.
+    /// Pending rewards are forfeited and the caller's score is removed from the aggregates.
+    #[endpoint(emergencyWithdraw)]
+    fn emergency_withdraw(&self) {
+        require!(self.emergency_mode().get(), ERR_NOT_EMERGENCY_MODE);
+        let caller = self.blockchain().get_caller();
+
+        let mut payments = ManagedVec::new();
+        for token_identifier in self.eligible_stake_token_identifiers().iter() {
+            let mut staked_nfts_mapper = self.staked_nfts(&caller, &token_identifier);
+            for (nonce, quantity) in staked_nfts_mapper.iter() {
+                payments.push(EsdtTokenPayment::new(
+                    token_identifier.clone(),
+                    nonce,
+                    quantity,
+                ));
+            }
+            staked_nfts_mapper.clear();
+        }
+
+        for unbonding_batch in self.unbonding_batches(&caller).values() {
+            payments.extend(&unbonding_batch.payload.get_payments());
+        }
+        for unbonding_payload in self.unbonding_assets(&caller).values() {
+            payments.extend(&unbonding_payload.get_payments());
+        }
+        self.unbonding_batches(&caller).clear();
+        self.unbonding_assets(&caller).clear();
+
+        // the withdrawn score leaves the aggregates so the remaining stakers keep their share
+        for staking_module_type in StakingModuleType::iter() {
+            let user_score = self
+                .aggregated_user_staking_score(staking_module_type, &caller)
+                .take();
+            update_unsettled_score(
+                self,
+                staking_module_type,
+                &caller,
+                &user_score,
+                &BigUint::zero(),
+            );
+            self.unsettled_user_score(staking_module_type, &caller)
+                .clear();
+            self.aggregated_staking_score(staking_module_type)
+                .update(|aggregated_score| {
+                    *aggregated_score = saturating_sub(aggregated_score, &user_score)
+                });
+            self.raw_aggregated_user_staking_score(staking_module_type, &caller)
+                .clear();
+        }
+        for reward_token in self.reward_registry().values() {
+            let reward_token_id = reward_token.token_identifier.clone();
+            let reward_token_nonce = reward_token.token_nonce;
+            self.pending_rewards(&caller, &reward_token_id, reward_token_nonce)
+                .clear();
+            if reward_token_nonce == 0 {
+                self.legacy_pending_rewards(&caller, &reward_token_id)
+                    .clear();
+            }
+            let reward_remainder = self
+                .pending_reward_remainder(&caller, &reward_token_id, reward_token_nonce)
+                .take();
+            // remainders from before a sweep were already dropped from the total
+            if !is_user_reward_stale(self, &caller, &reward_token) {
+                self.total_reward_remainder(&reward_token_id, reward_token_nonce)
+                    .update(|total_reward_remainder| {
+                        *total_reward_remainder =
+                            saturating_sub(total_reward_remainder, &reward_remainder)
+                    });
+            }
+            for staking_module_type in StakingModuleType::iter() {
+                self.user_reward_per_share(
+                    staking_module_type,
+                    &reward_token_id,
+                    reward_token_nonce,
+                    &caller,
+                )
+                .clear();
+            }
+        }
+
+        require!(!payments.is_empty(), ERR_NOTHING_TO_CLAIM);
+        self.send().direct_multi(&caller, &payments);
+        self.emergency_withdraw_event(&caller, &payments);
+    }
+
+    #[endpoint(triggerDistribution)]
+    fn trigger_distribution(
+        &self,
+        staking_module_type: StakingModuleType,
+        reward_token_identifier: EgldOrEsdtTokenIdentifier,
+        opt_reward_token_nonce: OptionalValue<u64>,
+    ) {
+        self.require_not_emergency_mode();
+        let reward_token_nonce = opt_reward_token_nonce.into_option().unwrap_or_default();
+        let distribution_amount = self
+            .reservoir_distribution_amount(
+                &staking_module_type,
+                &reward_token_identifier,
+                reward_token_nonce,
+            )
+            .get();
+        require!(distribution_amount > 0, ERR_NOTHING_TO_DISTRIBUTE);
+
+        let reservoir_mapper = self.reward_reservoir(
+            &staking_module_type,
+            &reward_token_identifier,
+            reward_token_nonce,
+        );
+        let reservoir = reservoir_mapper.get();
+        require!(reservoir > 0, ERR_NOTHING_TO_DISTRIBUTE);
+
+        let block_epoch = self.blockchain().get_block_epoch();
+        let last_triggered_epoch_mapper = self.last_triggered_distribution_epoch(
+            &staking_module_type,
+            &reward_token_identifier,
+            reward_token_nonce,
+        );
+        require!(
+            last_triggered_epoch_mapper.is_empty()
+                || last_triggered_epoch_mapper.get() < block_epoch,
+            ERR_REWARD_ALREADY_DISTRIBUTED
+        );
+        last_triggered_epoch_mapper.set(block_epoch);
+
+        let amount = core::cmp::min(distribution_amount, reservoir);
+        reservoir_mapper.update(|reservoir| *reservoir -= &amount);
+
+        self.distribute_reward_handler(
+            &staking_module_type,
+            EgldOrEsdtTokenPayment::new(reward_token_identifier, reward_token_nonce, amount),
+        );
     }
 
     #[endpoint(claimRewards)]
     fn claim_rewards(&self) {
+        let caller = self.blockchain().get_caller();
+        let receiver = self.get_reward_receiver(&caller);
+
+        self.claim_rewards_handler(&caller, &receiver);
+    }
+
+    /// Vested rewards are locked in the caller's schedule, `receiver` only gets the part paid
+    /// right away.
+    #[endpoint(claimRewardsTo)]
+    fn claim_rewards_to(&self, receiver: ManagedAddress) {
+        require!(!receiver.is_zero(), ERR_INVALID_REWARD_RECEIVER);
+        let caller = self.blockchain().get_caller();
+
+        self.claim_rewards_handler(&caller, &receiver);
+    }
+
+    #[endpoint(claimRewardsForTokens)]
+    fn claim_rewards_for_tokens(
+        &self,
+        token_identifiers: MultiValueEncoded<EgldOrEsdtTokenIdentifier>,
+    ) {
+        self.require_claim_rewards_not_paused();
         let caller = &self.blockchain().get_caller();
+        let receiver = self.get_reward_receiver(caller);
+
+        let pending_rewards =
+            claim_token_pending_rewards(self, caller, &token_identifiers.to_vec());
+        require!(!pending_rewards.is_empty(), ERR_NOTHING_TO_CLAIM);
+
+        self.send_rewards(caller, &receiver, &pending_rewards);
+    }
+
+    /// Settles up to `max_epochs` epochs of the per-epoch model for the caller. Positions holding
+    /// more than `MAX_LEGACY_SETTLEMENT_EPOCHS` unsettled epochs have to be settled this way before
+    /// their rewards can be secured.
+    #[endpoint(settleLegacyRewards)]
+    fn settle_legacy_rewards(&self, max_epochs: u64) {
+        let caller = self.blockchain().get_caller();
+        settle_user_legacy_rewards(self, &caller, max_epochs);
+    }
+
+    #[endpoint(setRewardReceiver)]
+    fn set_reward_receiver(&self, receiver: ManagedAddress) {
+        require!(!receiver.is_zero(), ERR_INVALID_REWARD_RECEIVER);
+        let caller = self.blockchain().get_caller();
+
+        self.reward_receiver(&caller).set(&receiver);
+        self.reward_receiver_event(&caller, &receiver);
+    }
+
+    #[endpoint(clearRewardReceiver)]
+    fn clear_reward_receiver(&self) {
+        let caller = self.blockchain().get_caller();
+
+        self.reward_receiver(&caller).clear();
+        self.reward_receiver_event(&caller, &caller);
+    }
+
+    fn claim_rewards_handler(&self, caller: &ManagedAddress, receiver: &ManagedAddress) {
+        self.require_claim_rewards_not_paused();
 
         let pending_rewards = claim_all_pending_rewards(self, caller);
-        require!(pending_rewards.len() > 0, ERR_NOTHING_TO_CLAIM);
+        require!(!pending_rewards.is_empty(), ERR_NOTHING_TO_CLAIM);
+
+        self.send_rewards(caller, receiver, &pending_rewards);
+    }
+
+    fn send_rewards(
+        &self,
+        caller: &ManagedAddress,
+        receiver: &ManagedAddress,
+        rewards: &ManagedVec<RewardPayment<Self::Api>>,
+    ) {
+        let paid_rewards = self.vest_rewards(caller, rewards);
+        send_reward_payments(self, receiver, &paid_rewards);
 
-        self.send().direct_multi(&caller, &pending_rewards);
+        self.claim_rewards_event(caller, receiver, rewards);
     }
 
     #[view(getPendingReward)]
-    fn get_pending_reward(&self, address: ManagedAddress) -> ManagedVec<EsdtTokenPayment> {
+    fn get_pending_reward(&self, address: ManagedAddress) -> ManagedVec<RewardPayment<Self::Api>> {
         let store_pending_rewards = false;
         get_all_pending_rewards(self, &address, store_pending_rewards)
     }
 
-    fn require_same_token_id(&self, payments: &ManagedVec<EsdtTokenPayment>) {
-        let token_id = payments.get(0).token_identifier.clone();
-        let other_token_id_payment = payments.iter().find(|p| p.token_identifier != token_id);
+    fn start_unbonding_payload(
+        &self,
+        payload: StartUnbondingPayload<Self::Api>,
+        secured_modules: &mut ManagedVec<u8>,
+    ) {
+        self.require_pool_not_paused(&payload.token_identifier);
+        let mut context = StakingContext::new(self, &payload.token_identifier);
+        let is_unbonding_successful =
+            context.start_unbonding_with_secured_modules(payload.clone(), secured_modules);
+        require!(is_unbonding_successful, ERR_FAILED_UNBONDING);
+        context.update_staking_scores();
+
+        let caller = self.blockchain().get_caller();
+        self.start_unbonding_event(
+            &caller,
+            &payload.token_identifier,
+            self.last_unbonding_batch_id(&caller).get(),
+            &context.get_event_data(payload.items),
+        );
+    }
+
+    fn collect_instant_unstake_fee(&self, collection_token_identifier: &TokenIdentifier) {
+        let staking_module_type = self
+            .stake_pool_type_configuration(collection_token_identifier)
+            .get();
+        let destination = self
+            .instant_unstake_fee_destination(collection_token_identifier)
+            .get();
+        let payments = self.call_value().all_esdt_transfers();
+
+        let InstantUnstakeFee::Payment(fee_payment) =
+            self.instant_unstake_fee(collection_token_identifier).get();
+        require!(payments.len() == 1, ERR_INVALID_FEE_PAYMENT);
+        let payment = payments.get(0);
+        require!(
+            payment.token_identifier == fee_payment.token_identifier
+                && payment.token_nonce == 0
+                && payment.amount == fee_payment.amount,
+            ERR_INVALID_FEE_PAYMENT
+        );
+
+        self.route_instant_unstake_fee(
+            &staking_module_type,
+            &destination,
+            &EgldOrEsdtTokenPayment::from(payment),
+        );
+    }
+
+    fn route_instant_unstake_fee(
+        &self,
+        staking_module_type: &StakingModuleType,
+        destination: &FeeDestination,
+        fee: &EgldOrEsdtTokenPayment,
+    ) {
+        match destination {
+            FeeDestination::Treasury => {
+                self.send().direct(
+                    &self.treasury_address().get(),
+                    &fee.token_identifier,
+                    fee.token_nonce,
+                    &fee.amount,
+                );
+            }
+            FeeDestination::Stakers => {
+                // fees in a token the pool does not earn go to the first module the token targets
+                let reward_token = self.get_reward_token(&fee.token_identifier, fee.token_nonce);
+                let staking_module_type = match reward_token.targets(staking_module_type)
+                    || reward_token.staking_modules.is_empty()
+                {
+                    true => staking_module_type.clone(),
+                    false => reward_token.staking_modules.get(0),
+                };
+                self.undistributed_rewards(
+                    &staking_module_type,
+                    &fee.token_identifier,
+                    fee.token_nonce,
+                )
+                .update(|undistributed_rewards| *undistributed_rewards += &fee.amount);
+            }
+        }
+    }
+
+    fn group_payments_by_token_id(
+        &self,
+        payments: &ManagedVec<EsdtTokenPayment>,
+    ) -> ManagedVec<ManagedVec<EsdtTokenPayment>> {
+        let mut token_ids = ManagedVec::<Self::Api, TokenIdentifier>::new();
+        for payment in payments.iter() {
+            if !token_ids.contains(&payment.token_identifier) {
+                token_ids.push(payment.token_identifier);
+            }
+        }
 
-        require!(other_token_id_payment.is_none(), ERR_ONE_TOKEN_ID_SUPPORTED);
+        token_ids
+            .iter()
+            .map(|token_id| {
+                payments
+                    .iter()
+                    .filter(|payment| payment.token_identifier == *token_id)
+                    .collect()
+            })
+            .collect()
     }
 
     fn require_unbonding_is_valid(&self, payload: &StartUnbondingPayload<Self::Api>) {
@@ -1,10 +1,25 @@
 use crate::{
     constants::{
         DEB_DENOMINATION, ERR_CANNOT_REGISTER_AS_ALL, ERR_COLLECTION_ALREADY_REGISTERED,
-        ERR_INVALID_REWARD_TOKEN_ID, ERR_REWARD_ALREADY_DISTRIBUTED,
+        ERR_FAILED_UNBONDING, ERR_INVALID_DISTRIBUTION_WEIGHTS, ERR_INVALID_FEE_CONFIGURATION,
+        ERR_INVALID_REWARD_SCHEDULE, ERR_INVALID_REWARD_TARGET, ERR_INVALID_REWARD_TOKEN_ID,
+        ERR_INVALID_REWARD_TOKEN_NONCE, ERR_INVALID_SWEEP_DEADLINE, ERR_NOTHING_TO_CLAIM,
+        ERR_NOTHING_TO_UNSTAKE, ERR_REWARDS_NOT_CLAIMED, ERR_REWARD_TOKEN_NOT_RETIRED,
+        ERR_REWARD_TOKEN_RETIRED, ERR_TREASURY_NOT_SET, MAX_PERCENTAGE, REWARD_PER_SHARE_PRECISION,
     },
+    staking_context::StakingContext,
     staking_modules::staking_module_type::StakingModuleType,
-    utils::secure_rewards,
+    types::{
+        event_data::{DebUpdateEventData, DistributionWeight, ForceUnstakeEventData, ScoreUpdate},
+        instant_unstake_fee::{FeeDestination, InstantUnstakeFee},
+        reward_schedule::RewardSchedule,
+        reward_token::{RewardToken, RewardTokenStatus},
+        start_unbonding_payload::StartUnbondingPayload,
+    },
+    utils::{
+        distribute_module_reward, migrate_legacy_unbonding_assets, release_scheduled_rewards,
+        secure_module_rewards, settle_user_legacy_rewards, update_unsettled_score,
+    },
 };
 
 multiversx_sc::imports!();
@@ -14,15 +29,18 @@ pub trait OwnerModule:
     crate::storage::config::ConfigModule
     + crate::storage::user_data::UserDataStorageModule
     + crate::storage::score::ScoreStorageModule
+    + crate::storage::pause::PauseStorageModule
+    + crate::pause::PauseModule
+    + crate::requirements::RequirementsModule
+    + crate::events::EventsModule
 {
     #[only_owner]
     #[payable("*")]
     #[endpoint(distributeGeneralReward)]
     fn distribute_reward(&self) {
-        let total_score = self.aggregated_staking_score(&StakingModuleType::All).get();
-        let payment = self.call_value().single_esdt();
+        let payment = self.call_value().egld_or_single_esdt();
 
-        self.distribute_reward_handler(&StakingModuleType::All, payment, total_score);
+        self.distribute_reward_handler(&StakingModuleType::All, payment);
     }
 
     #[only_owner]
@@ -30,10 +48,9 @@ pub trait OwnerModule:
     #[endpoint(distributeSecondaryReward)]
     fn distribute_secondary_reward(&self, target: TokenIdentifier) {
         let staking_module_type = self.stake_pool_type_configuration(&target).get();
-        let total_score = self.aggregated_staking_score(&staking_module_type).get();
-        let payment = self.call_value().single_esdt();
+        let payment = self.call_value().egld_or_single_esdt();
 
-        self.distribute_reward_handler(&staking_module_type, payment, total_score);
+        self.distribute_reward_handler(&staking_module_type, payment);
     }
 
     #[only_owner]
@@ -41,10 +58,97 @@ pub trait OwnerModule:
     #[endpoint(distributeCompanyShareReward)]
     fn distribute_company_share_reward(&self) {
         let staking_module_type = StakingModuleType::SharesSfts;
-        let total_score = self.aggregated_staking_score(&staking_module_type).get();
-        let payment = self.call_value().single_esdt();
+        let payment = self.call_value().egld_or_single_esdt();
 
-        self.distribute_reward_handler(&staking_module_type, payment, total_score);
+        self.distribute_reward_handler(&staking_module_type, payment);
+    }
+
+    #[only_owner]
+    #[payable("*")]
+    #[endpoint(distributeWeighted)]
+    fn distribute_weighted(&self) {
+        self.require_not_emergency_mode();
+        let payment = self.call_value().egld_or_single_esdt();
+        self.require_payment_is_reward(&payment);
+        let weights_mapper =
+            self.distribution_weights(&payment.token_identifier, payment.token_nonce);
+        require!(!weights_mapper.is_empty(), ERR_INVALID_DISTRIBUTION_WEIGHTS);
+
+        let block_epoch = self.blockchain().get_block_epoch();
+        self.record_distribution_timestamp(block_epoch, &payment.token_identifier);
+
+        // the last module receives the rounding remainder so the whole payment is distributed
+        let modules_count = weights_mapper.len();
+        let mut remaining_amount = payment.amount.clone();
+        for (index, (staking_module_type, weight)) in weights_mapper.iter().enumerate() {
+            let amount = if index + 1 == modules_count {
+                core::mem::take(&mut remaining_amount)
+            } else {
+                let amount = &payment.amount * weight / MAX_PERCENTAGE;
+                remaining_amount -= &amount;
+                amount
+            };
+            if amount == 0 {
+                continue;
+            }
+
+            distribute_module_reward(
+                self,
+                &staking_module_type,
+                EgldOrEsdtTokenPayment::new(
+                    payment.token_identifier.clone(),
+                    payment.token_nonce,
+                    amount,
+                ),
+                block_epoch,
+            );
+        }
+    }
+
+    #[only_owner]
+    #[endpoint(setDistributionWeights)]
+    fn set_distribution_weights(
+        &self,
+        reward_token_identifier: EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+        weights: MultiValueEncoded<MultiValue2<StakingModuleType, u64>>,
+    ) {
+        let reward_token =
+            self.require_token_is_reward_token(&reward_token_identifier, reward_token_nonce);
+
+        let mut weights_mapper =
+            self.distribution_weights(&reward_token_identifier, reward_token_nonce);
+        weights_mapper.clear();
+        let mut total_weight = 0u64;
+        let mut event_weights = ManagedVec::new();
+        for weight_entry in weights {
+            let (staking_module_type, weight) = weight_entry.into_tuple();
+            require!(
+                reward_token.targets(&staking_module_type),
+                ERR_INVALID_REWARD_TARGET
+            );
+            require!(
+                weight > 0
+                    && weights_mapper
+                        .insert(staking_module_type.clone(), weight)
+                        .is_none(),
+                ERR_INVALID_DISTRIBUTION_WEIGHTS
+            );
+            total_weight += weight;
+            event_weights.push(DistributionWeight {
+                staking_module_type,
+                weight,
+            });
+        }
+        require!(
+            total_weight == MAX_PERCENTAGE,
+            ERR_INVALID_DISTRIBUTION_WEIGHTS
+        );
+        self.distribution_weights_event(
+            &reward_token_identifier,
+            reward_token_nonce,
+            &event_weights,
+        );
     }
 
     #[only_owner]
@@ -64,44 +168,32 @@ pub trait OwnerModule:
             return;
         }
 
-        for reward_token_id in self.reward_token_identifiers().iter() {
-            let staking_module_type = self.stake_pool_type_configuration(&reward_token_id).get();
-            secure_rewards(
-                self,
-                &user_address,
-                &reward_token_id,
-                &staking_module_type,
-                None,
-            );
+        for staking_module_type in StakingModuleType::iter() {
+            if self
+                .raw_aggregated_user_staking_score(staking_module_type, &user_address)
+                .get()
+                == 0
+            {
+                continue;
+            }
+
+            secure_module_rewards(self, &user_address, staking_module_type);
 
             self.update_score_handler(
-                &staking_module_type,
+                staking_module_type,
                 &user_address,
+                &old_deb,
                 &new_deb,
                 &deb_denomination,
             );
         }
-
-        secure_rewards(
-            self,
-            &user_address,
-            &self.primary_reward_token_identifier().get(),
-            &StakingModuleType::All,
-            None,
-        );
-
-        self.update_score_handler(
-            &StakingModuleType::All,
-            &user_address,
-            &new_deb,
-            &deb_denomination,
-        );
     }
 
     fn update_score_handler(
         &self,
         staking_module_type: &StakingModuleType,
         user_address: &ManagedAddress,
+        old_deb: &BigUint,
         new_deb: &BigUint,
         deb_denomination: &BigUint,
     ) {
@@ -119,59 +211,230 @@ pub trait OwnerModule:
         let current_general_score = self.aggregated_staking_score(staking_module_type).get();
         let new_score_after_deb = &current_score * new_deb / deb_denomination;
 
+        let new_general_score =
+            &current_general_score - &current_score_with_deb + &new_score_after_deb;
+
+        update_unsettled_score(
+            self,
+            staking_module_type,
+            user_address,
+            &current_score_with_deb,
+            &new_score_after_deb,
+        );
         self.aggregated_user_staking_score(staking_module_type, &user_address)
             .set(&new_score_after_deb);
         self.aggregated_staking_score(staking_module_type)
-            .set(&current_general_score - &current_score_with_deb + &new_score_after_deb);
+            .set(&new_general_score);
+
+        self.update_deb_event(
+            user_address,
+            staking_module_type,
+            &DebUpdateEventData {
+                previous_deb: old_deb.clone(),
+                deb: new_deb.clone(),
+                score_update: ScoreUpdate {
+                    previous_user_score: current_score_with_deb,
+                    user_score: new_score_after_deb,
+                    aggregated_score: new_general_score,
+                },
+            },
+        );
+    }
+
+    fn record_distribution_timestamp(
+        &self,
+        epoch: u64,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+    ) {
+        let block_timestamp = self.blockchain().get_block_timestamp();
+        self.reward_distribution_timestamp(epoch, reward_token_identifier)
+            .set(block_timestamp);
+        self.reward_distribution_timestamps(epoch, reward_token_identifier)
+            .push(&block_timestamp);
     }
 
     fn distribute_reward_handler(
         &self,
         staking_module_type: &StakingModuleType,
-        payment: EsdtTokenPayment,
-        total_score: BigUint,
+        payment: EgldOrEsdtTokenPayment,
     ) {
-        self.require_token_is_reward_token(&payment.token_identifier);
+        self.require_not_emergency_mode();
+        let reward_token = self.require_payment_is_reward(&payment);
+        require!(
+            reward_token.targets(staking_module_type),
+            ERR_INVALID_REWARD_TARGET
+        );
         let block_epoch = self.blockchain().get_block_epoch();
-        let block_timestamp = self.blockchain().get_block_timestamp();
+        self.record_distribution_timestamp(block_epoch, &payment.token_identifier);
 
-        self.require_reward_not_distributed(
-            block_epoch,
-            staking_module_type,
+        distribute_module_reward(self, staking_module_type, payment, block_epoch);
+    }
+
+    #[only_owner]
+    #[payable("*")]
+    #[endpoint(fundRewardSchedule)]
+    fn fund_reward_schedule(&self, staking_module_type: StakingModuleType, epochs: u64) {
+        let payment = self.call_value().egld_or_single_esdt();
+        let reward_token = self.require_payment_is_reward(&payment);
+        require!(
+            reward_token.targets(&staking_module_type),
+            ERR_INVALID_REWARD_TARGET
+        );
+        require!(epochs > 0, ERR_INVALID_REWARD_SCHEDULE);
+
+        // settle the epochs already elapsed before merging the new funds into the schedule
+        release_scheduled_rewards(self, &staking_module_type, &reward_token);
+
+        let reward_schedule_mapper = self.reward_schedule(
+            &staking_module_type,
             &payment.token_identifier,
+            payment.token_nonce,
         );
-        let reward_rate = payment.amount / total_score;
+        let remaining_amount = match reward_schedule_mapper.is_empty() {
+            true => BigUint::zero(),
+            false => reward_schedule_mapper.get().remaining_amount,
+        };
+        reward_schedule_mapper.set(RewardSchedule::new(
+            remaining_amount + &payment.amount,
+            epochs,
+            self.blockchain().get_block_epoch(),
+        ));
+
+        self.fund_reward_schedule_event(&staking_module_type, epochs, &payment);
+    }
+
+    #[only_owner]
+    #[payable("*")]
+    #[endpoint(depositRewardReservoir)]
+    fn deposit_reward_reservoir(&self, staking_module_type: StakingModuleType) {
+        let payment = self.call_value().egld_or_single_esdt();
+        let reward_token = self.require_payment_is_reward(&payment);
+        require!(
+            reward_token.targets(&staking_module_type),
+            ERR_INVALID_REWARD_TARGET
+        );
+
+        self.reward_reservoir(
+            &staking_module_type,
+            &payment.token_identifier,
+            payment.token_nonce,
+        )
+        .update(|reservoir| *reservoir += &payment.amount);
 
-        self.reward_rate(block_epoch, staking_module_type, &payment.token_identifier)
-            .set(reward_rate);
-        self.reward_distribution_timestamp(block_epoch, &payment.token_identifier)
-            .set(&block_timestamp);
+        self.deposit_reward_reservoir_event(&staking_module_type, &payment);
     }
 
-    fn require_reward_not_distributed(
+    #[only_owner]
+    #[endpoint(withdrawRewardReservoir)]
+    fn withdraw_reward_reservoir(
         &self,
-        epoch: u64,
-        staking_module_type: &StakingModuleType,
-        token_identifier: &TokenIdentifier,
+        staking_module_type: StakingModuleType,
+        reward_token_identifier: EgldOrEsdtTokenIdentifier,
+        opt_reward_token_nonce: OptionalValue<u64>,
     ) {
-        require!(
-            self.reward_distribution_timestamp(epoch, token_identifier)
-                .is_empty()
-                && self
-                    .reward_rate(epoch, staking_module_type, token_identifier)
-                    .is_empty(),
-            ERR_REWARD_ALREADY_DISTRIBUTED
+        let reward_token_nonce = opt_reward_token_nonce.into_option().unwrap_or_default();
+        let reservoir = self
+            .reward_reservoir(
+                &staking_module_type,
+                &reward_token_identifier,
+                reward_token_nonce,
+            )
+            .take();
+        require!(reservoir > 0, ERR_NOTHING_TO_CLAIM);
+
+        let caller = self.blockchain().get_caller();
+        self.send().direct(
+            &caller,
+            &reward_token_identifier,
+            reward_token_nonce,
+            &reservoir,
+        );
+        self.withdraw_reward_reservoir_event(
+            &staking_module_type,
+            &EgldOrEsdtTokenPayment::new(reward_token_identifier, reward_token_nonce, reservoir),
         );
     }
 
-    fn require_token_is_reward_token(&self, incoming_token_identifier: &TokenIdentifier) {
+    #[only_owner]
+    #[endpoint(setReservoirDistributionAmount)]
+    fn set_reservoir_distribution_amount(
+        &self,
+        staking_module_type: StakingModuleType,
+        reward_token_identifier: EgldOrEsdtTokenIdentifier,
+        amount: BigUint,
+        opt_reward_token_nonce: OptionalValue<u64>,
+    ) {
+        let reward_token_nonce = opt_reward_token_nonce.into_option().unwrap_or_default();
+        let reward_token =
+            self.require_token_is_reward_token(&reward_token_identifier, reward_token_nonce);
         require!(
-            self.reward_token_identifiers()
-                .contains(incoming_token_identifier),
-            ERR_INVALID_REWARD_TOKEN_ID
+            reward_token.targets(&staking_module_type),
+            ERR_INVALID_REWARD_TARGET
+        );
+
+        self.reservoir_distribution_amount(
+            &staking_module_type,
+            &reward_token_identifier,
+            reward_token_nonce,
+        )
+        .set(&amount);
+        self.reservoir_distribution_amount_event(
+            &staking_module_type,
+            &reward_token_identifier,
+            reward_token_nonce,
+            &amount,
+        );
+    }
+
+    #[only_owner]
+    #[endpoint(recoverZeroScoreReserve)]
+    fn recover_zero_score_reserve(
+        &self,
+        staking_module_type: StakingModuleType,
+        reward_token_identifier: EgldOrEsdtTokenIdentifier,
+        opt_reward_token_nonce: OptionalValue<u64>,
+    ) {
+        let reward_token_nonce = opt_reward_token_nonce.into_option().unwrap_or_default();
+        let reserve = self
+            .zero_score_reserve(
+                &staking_module_type,
+                &reward_token_identifier,
+                reward_token_nonce,
+            )
+            .take();
+        require!(reserve > 0, ERR_NOTHING_TO_CLAIM);
+
+        let caller = self.blockchain().get_caller();
+        self.send().direct(
+            &caller,
+            &reward_token_identifier,
+            reward_token_nonce,
+            &reserve,
+        );
+        self.recover_zero_score_reserve_event(
+            &staking_module_type,
+            &EgldOrEsdtTokenPayment::new(reward_token_identifier, reward_token_nonce, reserve),
         );
     }
 
+    fn require_token_is_reward_token(
+        &self,
+        incoming_token_identifier: &EgldOrEsdtTokenIdentifier,
+        incoming_token_nonce: u64,
+    ) -> RewardToken<Self::Api> {
+        let reward_token = self.get_reward_token(incoming_token_identifier, incoming_token_nonce);
+        require!(reward_token.is_active(), ERR_REWARD_TOKEN_RETIRED);
+
+        reward_token
+    }
+
+    fn require_payment_is_reward(
+        &self,
+        payment: &EgldOrEsdtTokenPayment,
+    ) -> RewardToken<Self::Api> {
+        self.require_token_is_reward_token(&payment.token_identifier, payment.token_nonce)
+    }
+
     #[only_owner]
     #[endpoint(createPool)]
     fn register_new_staking_pool(
@@ -189,12 +452,14 @@ pub trait OwnerModule:
             ERR_COLLECTION_ALREADY_REGISTERED
         );
         self.stake_pool_type_configuration(&collection_token_identifier)
-            .set(staking_module_type);
+            .set(&staking_module_type);
         require!(
             self.eligible_stake_token_identifiers()
-                .insert(collection_token_identifier),
+                .insert(collection_token_identifier.clone()),
             ERR_COLLECTION_ALREADY_REGISTERED
         );
+
+        self.create_pool_event(&collection_token_identifier, &staking_module_type);
     }
 
     #[only_owner]
@@ -205,7 +470,9 @@ pub trait OwnerModule:
         staking_module_type: StakingModuleType,
     ) {
         self.stake_pool_type_configuration(&collection_token_identifier)
-            .set(staking_module_type);
+            .set(&staking_module_type);
+
+        self.override_pool_type_event(&collection_token_identifier, &staking_module_type);
     }
 
     #[only_owner]
@@ -218,6 +485,8 @@ pub trait OwnerModule:
     ) {
         self.base_asset_score(collection_token_identifier, staking_module)
             .set(&score);
+
+        self.base_asset_score_event(collection_token_identifier, staking_module, score);
     }
 
     #[only_owner]
@@ -229,10 +498,13 @@ pub trait OwnerModule:
         score: usize,
         nonces: MultiValueEncoded<u64>,
     ) {
-        for nonce in nonces.to_vec().iter() {
+        let nonces = nonces.to_vec();
+        for nonce in nonces.iter() {
             self.nonce_asset_score(collection_token_identifier, nonce, staking_module)
                 .set(&score);
         }
+
+        self.nonce_asset_score_event(collection_token_identifier, staking_module, score, &nonces);
     }
 
     #[only_owner]
@@ -249,17 +521,235 @@ pub trait OwnerModule:
             self.nonce_asset_score(collection_token_identifier, nonce, staking_module)
                 .set(&score);
         }
+
+        self.nonce_asset_score_by_range_event(
+            collection_token_identifier,
+            staking_module,
+            nonce_range_start,
+            nonce_range_end,
+            score,
+        );
     }
 
     #[only_owner]
     #[endpoint(registerRewardToken)]
     fn register_reward_token(
         &self,
-        reward_token_identifier: TokenIdentifier,
+        reward_token_identifier: EgldOrEsdtTokenIdentifier,
         staking_module_type: StakingModuleType,
+        opt_reward_token_nonce: OptionalValue<u64>,
+    ) {
+        require!(
+            reward_token_identifier.is_valid() && staking_module_type != StakingModuleType::Invalid,
+            ERR_INVALID_REWARD_TOKEN_ID
+        );
+
+        // every nonce of an SFT collection is a reward token of its own, paid out in whole items
+        let reward_token_nonce = opt_reward_token_nonce.into_option().unwrap_or_default();
+        require!(
+            reward_token_nonce == 0 || reward_token_identifier.is_esdt(),
+            ERR_INVALID_REWARD_TOKEN_NONCE
+        );
+
+        let reward_token_key = (reward_token_identifier.clone(), reward_token_nonce);
+        let mut reward_token = match self.reward_registry().get(&reward_token_key) {
+            Some(reward_token) => reward_token,
+            // swept tokens start over from the accumulators cleared by the sweep
+            None => RewardToken::new(
+                reward_token_identifier.clone(),
+                reward_token_nonce,
+                REWARD_PER_SHARE_PRECISION,
+                self.reward_token_generation(&reward_token_identifier, reward_token_nonce)
+                    .get(),
+            ),
+        };
+        require!(reward_token.is_active(), ERR_REWARD_TOKEN_RETIRED);
+
+        if !reward_token.targets(&staking_module_type) {
+            reward_token
+                .staking_modules
+                .push(staking_module_type.clone());
+        }
+        self.reward_registry()
+            .insert(reward_token_key, reward_token);
+        self.register_reward_token_event(
+            &reward_token_identifier,
+            reward_token_nonce,
+            &staking_module_type,
+        );
+    }
+
+    #[only_owner]
+    #[endpoint(retireRewardToken)]
+    fn retire_reward_token(
+        &self,
+        reward_token_identifier: EgldOrEsdtTokenIdentifier,
+        sweep_deadline: u64,
+        opt_reward_token_nonce: OptionalValue<u64>,
+    ) {
+        let reward_token_nonce = opt_reward_token_nonce.into_option().unwrap_or_default();
+        let mut reward_token =
+            self.require_token_is_reward_token(&reward_token_identifier, reward_token_nonce);
+        require!(
+            sweep_deadline > self.blockchain().get_block_epoch(),
+            ERR_INVALID_SWEEP_DEADLINE
+        );
+
+        reward_token.status = RewardTokenStatus::Retired;
+        reward_token.sweep_deadline = sweep_deadline;
+        self.retire_reward_token_event(
+            &reward_token_identifier,
+            reward_token_nonce,
+            sweep_deadline,
+            &reward_token.get_unclaimed_amount(),
+        );
+        self.reward_registry()
+            .insert((reward_token_identifier, reward_token_nonce), reward_token);
+    }
+
+    #[only_owner]
+    #[endpoint(sweepRetiredRewardToken)]
+    fn sweep_retired_reward_token(
+        &self,
+        reward_token_identifier: EgldOrEsdtTokenIdentifier,
+        opt_reward_token_nonce: OptionalValue<u64>,
+    ) {
+        let reward_token_nonce = opt_reward_token_nonce.into_option().unwrap_or_default();
+        let reward_token = self.get_reward_token(&reward_token_identifier, reward_token_nonce);
+        require!(
+            reward_token.status == RewardTokenStatus::Retired,
+            ERR_REWARD_TOKEN_NOT_RETIRED
+        );
+        // fractions of a unit left to users can never be claimed, they are swept as dust.
+        // Unsettled positions of the per-epoch model are missing from the totals, those tokens
+        // are only swept at the deadline
+        let unclaimed_rewards = reward_token.get_unclaimed_amount();
+        let total_reward_remainder = self
+            .total_reward_remainder(&reward_token_identifier, reward_token_nonce)
+            .take();
+        let has_legacy_positions = reward_token_nonce == 0
+            && self
+                .legacy_reward_tokens()
+                .contains(&reward_token_identifier);
+        require!(
+            (!has_legacy_positions
+                && &unclaimed_rewards * &reward_token.precision <= total_reward_remainder)
+                || self.blockchain().get_block_epoch() >= reward_token.sweep_deadline,
+            ERR_REWARDS_NOT_CLAIMED
+        );
+
+        // rewards nobody claimed by the deadline are swept along with the undistributed funds,
+        // amounts locked in vesting schedules stay withdrawable
+        let mut sweep_amount = unclaimed_rewards;
+        for staking_module_type in StakingModuleType::iter() {
+            sweep_amount += self
+                .reward_reservoir(
+                    staking_module_type,
+                    &reward_token_identifier,
+                    reward_token_nonce,
+                )
+                .take();
+            sweep_amount += self
+                .zero_score_reserve(
+                    staking_module_type,
+                    &reward_token_identifier,
+                    reward_token_nonce,
+                )
+                .take();
+            sweep_amount += self
+                .undistributed_rewards(
+                    staking_module_type,
+                    &reward_token_identifier,
+                    reward_token_nonce,
+                )
+                .take();
+            sweep_amount += self
+                .reward_dust(
+                    staking_module_type,
+                    &reward_token_identifier,
+                    reward_token_nonce,
+                )
+                .take();
+            let reward_schedule_mapper = self.reward_schedule(
+                staking_module_type,
+                &reward_token_identifier,
+                reward_token_nonce,
+            );
+            if !reward_schedule_mapper.is_empty() {
+                sweep_amount += reward_schedule_mapper.take().remaining_amount;
+            }
+            self.reservoir_distribution_amount(
+                staking_module_type,
+                &reward_token_identifier,
+                reward_token_nonce,
+            )
+            .clear();
+            self.reward_per_share(
+                staking_module_type,
+                &reward_token_identifier,
+                reward_token_nonce,
+            )
+            .clear();
+            self.last_distribution_epoch(
+                staking_module_type,
+                &reward_token_identifier,
+                reward_token_nonce,
+            )
+            .clear();
+        }
+        self.distribution_weights(&reward_token_identifier, reward_token_nonce)
+            .clear();
+
+        // vesting schedules keep their nonce, vested amounts stay withdrawable after the removal
+        self.reward_registry()
+            .remove(&(reward_token_identifier.clone(), reward_token_nonce));
+        self.reward_token_generation(&reward_token_identifier, reward_token_nonce)
+            .set(reward_token.generation + 1);
+        // legacy positions are discarded along with every other reward of the swept generation
+        if has_legacy_positions {
+            self.legacy_reward_tokens()
+                .swap_remove(&reward_token_identifier);
+        }
+        self.vesting_config(&reward_token_identifier, reward_token_nonce)
+            .clear();
+
+        let balance = self
+            .blockchain()
+            .get_sc_balance(&reward_token_identifier, reward_token_nonce);
+        let payment = EgldOrEsdtTokenPayment::new(
+            reward_token_identifier,
+            reward_token_nonce,
+            core::cmp::min(sweep_amount, balance),
+        );
+        if payment.amount > 0 {
+            self.send().direct(
+                &self.blockchain().get_caller(),
+                &payment.token_identifier,
+                payment.token_nonce,
+                &payment.amount,
+            );
+        }
+
+        self.sweep_reward_token_event(&payment.token_identifier, &payment);
+    }
+
+    /// Settles up to `max_epochs` epochs of the per-epoch model across the given users, the
+    /// progress is stored so heavy positions can be settled over several calls.
+    #[only_owner]
+    #[endpoint(migrateRewardCheckpoints)]
+    fn migrate_reward_checkpoints(
+        &self,
+        max_epochs: u64,
+        users: MultiValueEncoded<ManagedAddress>,
     ) {
-        self.reward_token_id_mapping()
-            .insert((reward_token_identifier, staking_module_type));
+        let mut remaining_epochs = max_epochs;
+        for user in users.into_iter() {
+            if remaining_epochs == 0 {
+                break;
+            }
+
+            remaining_epochs = settle_user_legacy_rewards(self, &user, remaining_epochs);
+        }
     }
 
     #[only_owner]
@@ -272,30 +762,134 @@ pub trait OwnerModule:
     ) {
         self.full_set_score(collection_token_identifier, staking_module)
             .set(&score);
+
+        self.full_set_score_event(collection_token_identifier, staking_module, score);
     }
 
     #[only_owner]
-    #[endpoint(reset)]
-    fn reset(
+    #[endpoint(setUnbondingTimePenalty)]
+    fn set_unbonding_time_penalty(&self, unbonding_time_penalty: u64) {
+        self.unbonding_time_penalty().set(unbonding_time_penalty);
+        self.unbonding_time_penalty_event(unbonding_time_penalty);
+    }
+
+    #[only_owner]
+    #[endpoint(setPoolUnbondingTimePenalty)]
+    fn set_pool_unbonding_time_penalty(
         &self,
-        user: ManagedAddress,
-        token_identifier: TokenIdentifier,
-        nonce: u64,
-        amount: BigUint,
+        collection_token_identifier: TokenIdentifier,
+        unbonding_time_penalty: u64,
     ) {
-        self.staked_nfts(&user, &token_identifier).clear();
-        self.unbonding_assets(&user).clear();
-        for staking_type in StakingModuleType::iter() {
-            self.raw_aggregated_user_staking_score(&staking_type, &user)
-                .set(&BigUint::zero());
-            self.aggregated_user_staking_score(&staking_type, &user)
-                .set(&BigUint::zero());
-            self.aggregated_staking_score(&staking_type).clear();
+        self.pool_unbonding_time_penalty(&collection_token_identifier)
+            .set(unbonding_time_penalty);
+        self.pool_unbonding_time_penalty_event(
+            &collection_token_identifier,
+            unbonding_time_penalty,
+        );
+    }
+
+    #[only_owner]
+    #[endpoint(removePoolUnbondingTimePenalty)]
+    fn remove_pool_unbonding_time_penalty(&self, collection_token_identifier: TokenIdentifier) {
+        self.pool_unbonding_time_penalty(&collection_token_identifier)
+            .clear();
+        self.remove_pool_unbonding_time_penalty_event(&collection_token_identifier);
+    }
+
+    #[only_owner]
+    #[endpoint(setTreasuryAddress)]
+    fn set_treasury_address(&self, treasury_address: ManagedAddress) {
+        self.treasury_address().set(&treasury_address);
+        self.treasury_address_event(&treasury_address);
+    }
+
+    #[only_owner]
+    #[endpoint(setInstantUnstakeFee)]
+    fn set_instant_unstake_fee(
+        &self,
+        collection_token_identifier: TokenIdentifier,
+        fee: InstantUnstakeFee<Self::Api>,
+        destination: FeeDestination,
+    ) {
+        let InstantUnstakeFee::Payment(fee_payment) = &fee;
+        require!(
+            fee_payment.token_identifier.is_valid_esdt_identifier() && fee_payment.token_nonce == 0,
+            ERR_INVALID_FEE_CONFIGURATION
+        );
+        if destination == FeeDestination::Stakers {
+            let reward_token = self.require_token_is_reward_token(
+                &EgldOrEsdtTokenIdentifier::esdt(fee_payment.token_identifier.clone()),
+                0,
+            );
+            let staking_module_type = self
+                .stake_pool_type_configuration(&collection_token_identifier)
+                .get();
+            require!(
+                reward_token.targets(&staking_module_type),
+                ERR_INVALID_FEE_CONFIGURATION
+            );
         }
-        if &amount == &0 {
-            return;
+        if destination == FeeDestination::Treasury {
+            require!(!self.treasury_address().is_empty(), ERR_TREASURY_NOT_SET);
+        }
+
+        self.instant_unstake_fee(&collection_token_identifier)
+            .set(&fee);
+        self.instant_unstake_fee_destination(&collection_token_identifier)
+            .set(&destination);
+        self.instant_unstake_fee_event(&collection_token_identifier, &destination, &fee);
+    }
+
+    #[only_owner]
+    #[endpoint(disableInstantUnstake)]
+    fn disable_instant_unstake(&self, collection_token_identifier: TokenIdentifier) {
+        self.instant_unstake_fee(&collection_token_identifier)
+            .clear();
+        self.instant_unstake_fee_destination(&collection_token_identifier)
+            .clear();
+        self.disable_instant_unstake_event(&collection_token_identifier);
+    }
+
+    #[only_owner]
+    #[endpoint(forceUnstake)]
+    fn force_unstake(&self, user: ManagedAddress, token_identifier: TokenIdentifier) {
+        let mut payments = ManagedVec::new();
+        let mut staking_event_data = None;
+
+        let staked_nfts = self.get_staked_nfts(&user, &token_identifier);
+        if !staked_nfts.is_empty() {
+            let payload = StartUnbondingPayload::new(token_identifier.clone(), staked_nfts);
+            let mut context =
+                StakingContext::new_with_address(self, &token_identifier, user.clone());
+            require!(context.unstake(payload.clone()), ERR_FAILED_UNBONDING);
+            context.update_staking_scores();
+
+            payments.extend(&payload.get_payments());
+            staking_event_data = Some(context.get_event_data(payload.items));
+        }
+
+        migrate_legacy_unbonding_assets(self, &user);
+        let mut unbonding_batches_mapper = self.unbonding_batches(&user);
+        let mut unbonding_batch_ids = ManagedVec::<Self::Api, u64>::new();
+        for (batch_id, unbonding_batch) in unbonding_batches_mapper.iter() {
+            if unbonding_batch.token_identifier() == &token_identifier {
+                payments.extend(&unbonding_batch.payload.get_payments());
+                unbonding_batch_ids.push(batch_id);
+            }
         }
-        self.send()
-            .direct_esdt(&user, &token_identifier, nonce, &amount);
+        for batch_id in unbonding_batch_ids.iter() {
+            unbonding_batches_mapper.remove(&batch_id);
+        }
+
+        require!(!payments.is_empty(), ERR_NOTHING_TO_UNSTAKE);
+        self.send().direct_multi(&user, &payments);
+        self.force_unstake_event(
+            &user,
+            &token_identifier,
+            &ForceUnstakeEventData {
+                payments,
+                staking_event_data,
+            },
+        );
     }
 }
@@ -0,0 +1,99 @@
+use crate::constants::{
+    ERR_CONTRACT_PAUSED, ERR_EMERGENCY_MODE, ERR_NOT_OWNER_OR_PAUSER, ERR_POOL_PAUSED,
+};
+
+multiversx_sc::imports!();
+
+#[multiversx_sc::module]
+pub trait PauseModule:
+    crate::storage::pause::PauseStorageModule + crate::events::EventsModule
+{
+    #[only_owner]
+    #[endpoint(addPauser)]
+    fn add_pauser(&self, address: ManagedAddress) {
+        self.pausers().insert(address.clone());
+        self.add_pauser_event(&address);
+    }
+
+    #[only_owner]
+    #[endpoint(removePauser)]
+    fn remove_pauser(&self, address: ManagedAddress) {
+        self.pausers().swap_remove(&address);
+        self.remove_pauser_event(&address);
+    }
+
+    #[only_owner]
+    #[endpoint(setClaimRewardsPausable)]
+    fn set_claim_rewards_pausable(&self, pausable: bool) {
+        self.claim_rewards_pausable().set(pausable);
+        self.claim_rewards_pausable_event(pausable);
+    }
+
+    #[only_owner]
+    #[endpoint(setEmergencyMode)]
+    fn set_emergency_mode(&self, enabled: bool) {
+        self.emergency_mode().set(enabled);
+        self.emergency_mode_event(enabled);
+    }
+
+    #[endpoint(pause)]
+    fn pause(&self) {
+        self.require_owner_or_pauser();
+        self.paused().set(true);
+        self.pause_event(&self.blockchain().get_caller());
+    }
+
+    #[endpoint(unpause)]
+    fn unpause(&self) {
+        self.require_owner_or_pauser();
+        self.paused().clear();
+        self.unpause_event(&self.blockchain().get_caller());
+    }
+
+    #[endpoint(pausePool)]
+    fn pause_pool(&self, collection_token_identifier: TokenIdentifier) {
+        self.require_owner_or_pauser();
+        self.pool_paused(&collection_token_identifier).set(true);
+        self.pause_pool_event(
+            &self.blockchain().get_caller(),
+            &collection_token_identifier,
+        );
+    }
+
+    #[endpoint(unpausePool)]
+    fn unpause_pool(&self, collection_token_identifier: TokenIdentifier) {
+        self.require_owner_or_pauser();
+        self.pool_paused(&collection_token_identifier).clear();
+        self.unpause_pool_event(
+            &self.blockchain().get_caller(),
+            &collection_token_identifier,
+        );
+    }
+
+    fn require_pool_not_paused(&self, collection_token_identifier: &TokenIdentifier) {
+        require!(!self.paused().get(), ERR_CONTRACT_PAUSED);
+        require!(
+            !self.pool_paused(collection_token_identifier).get(),
+            ERR_POOL_PAUSED
+        );
+    }
+
+    fn require_not_emergency_mode(&self) {
+        require!(!self.emergency_mode().get(), ERR_EMERGENCY_MODE);
+    }
+
+    fn require_claim_rewards_not_paused(&self) {
+        require!(
+            !self.claim_rewards_pausable().get() || !self.paused().get(),
+            ERR_CONTRACT_PAUSED
+        );
+    }
+
+    fn require_owner_or_pauser(&self) {
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == self.blockchain().get_owner_address() || self.pausers().contains(&caller),
+            ERR_NOT_OWNER_OR_PAUSER
+        );
+    }
+}
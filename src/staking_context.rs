@@ -3,8 +3,13 @@ use crate::{
     staking_modules::staking_module_type::{
         StakingModuleType, StakingModuleTypeFactory, StakingModuleTypeMapping, VestaStakingModule,
     },
-    types::start_unbonding_payload::StartUnbondingPayload,
-    utils::secure_rewards,
+    types::{
+        event_data::{ScoreUpdate, StakingEventData},
+        nonce_qty_pair::NonceQtyPair,
+        start_unbonding_payload::StartUnbondingPayload,
+        unbonding_batch::UnbondingBatch,
+    },
+    utils::{migrate_legacy_unbonding_assets, secure_module_rewards, update_unsettled_score},
 };
 
 multiversx_sc::imports!();
@@ -24,6 +29,7 @@ where
     secondary_aggregated_user_score_with_deb: BigUint<C::Api>,
     initial_pool_user_score: BigUint<C::Api>,
     secondary_initial_pool_user_score: BigUint<C::Api>,
+    initial_shares_user_score: Option<BigUint<C::Api>>,
     staking_module_type: StakingModuleType,
     staking_module_impl: StakingModuleTypeMapping<'a, C>,
     token_identifier: TokenIdentifier<C::Api>,
@@ -35,6 +41,8 @@ where
     C: crate::storage::score::ScoreStorageModule,
     C: crate::storage::user_data::UserDataStorageModule,
     C: crate::requirements::RequirementsModule,
+    C: crate::events::EventsModule,
+    C: crate::storage::pause::PauseStorageModule,
 {
     pub fn new(sc_ref: &'a C, payment_token_id: &TokenIdentifier<C::Api>) -> Self {
         let caller = sc_ref.blockchain().get_caller();
@@ -64,6 +72,14 @@ where
 
         let secondary_initial_pool_user_score =
             staking_module_impl.get_base_user_score(&staking_module_type);
+        let initial_shares_user_score = match staking_module_type == StakingModuleType::SnakesSfts {
+            true => Some(
+                sc_ref
+                    .aggregated_user_staking_score(&StakingModuleType::SharesSfts, &caller)
+                    .get(),
+            ),
+            false => None,
+        };
 
         Self {
             sc_ref,
@@ -75,6 +91,7 @@ where
             secondary_aggregated_user_score_with_deb,
             initial_pool_user_score,
             secondary_initial_pool_user_score,
+            initial_shares_user_score,
             staking_module_type,
             staking_module_impl,
             token_identifier: payment_token_id.clone(),
@@ -82,7 +99,17 @@ where
     }
 
     pub fn add_to_stake(&mut self, payments: &ManagedVec<C::Api, EsdtTokenPayment<C::Api>>) {
-        self.secure_all_rewards();
+        self.add_to_stake_with_secured_modules(payments, &mut ManagedVec::new());
+    }
+
+    /// Same as `add_to_stake`, but skips securing the modules already listed in `secured_modules`.
+    /// Used when several contexts of the same user are processed in a single call.
+    pub fn add_to_stake_with_secured_modules(
+        &mut self,
+        payments: &ManagedVec<C::Api, EsdtTokenPayment<C::Api>>,
+        secured_modules: &mut ManagedVec<C::Api, u8>,
+    ) {
+        self.secure_rewards(secured_modules);
         for payment in payments.iter() {
             self.sc_ref
                 .require_token_matches(&self.token_identifier, &payment.token_identifier);
@@ -92,31 +119,74 @@ where
         }
     }
 
+    pub fn unstake(&mut self, payload: StartUnbondingPayload<C::Api>) -> bool {
+        self.unstake_with_secured_modules(payload, &mut ManagedVec::new())
+    }
+
+    pub fn unstake_with_secured_modules(
+        &mut self,
+        payload: StartUnbondingPayload<C::Api>,
+        secured_modules: &mut ManagedVec<C::Api, u8>,
+    ) -> bool {
+        self.secure_rewards(secured_modules);
+
+        self.staking_module_impl.start_unbonding(payload)
+    }
+
     pub fn start_unbonding(&mut self, payload: StartUnbondingPayload<C::Api>) -> bool {
-        self.secure_all_rewards();
+        self.start_unbonding_with_secured_modules(payload, &mut ManagedVec::new())
+    }
 
-        let unbonding_result = self.staking_module_impl.start_unbonding(payload.clone());
+    pub fn start_unbonding_with_secured_modules(
+        &mut self,
+        payload: StartUnbondingPayload<C::Api>,
+        secured_modules: &mut ManagedVec<C::Api, u8>,
+    ) -> bool {
+        let unbonding_result = self.unstake_with_secured_modules(payload.clone(), secured_modules);
         if unbonding_result {
+            migrate_legacy_unbonding_assets(self.sc_ref, &self.caller);
+
+            let batch_id = self.sc_ref.get_next_unbonding_batch_id(&self.caller);
+            let unbonding_batch = UnbondingBatch::new(
+                payload,
+                self.sc_ref.blockchain().get_block_timestamp(),
+                self.sc_ref
+                    .get_pool_unbonding_time_penalty(&self.token_identifier),
+            );
             self.sc_ref
-                .unbonding_assets(&self.caller)
-                .insert(self.sc_ref.blockchain().get_block_timestamp(), payload);
+                .unbonding_batches(&self.caller)
+                .insert(batch_id, unbonding_batch);
         }
 
         unbonding_result
     }
 
-    fn secure_all_rewards(&self) {
-        for reward_token_id in self.sc_ref.reward_token_identifiers().iter() {
-            secure_rewards(
-                self.sc_ref,
-                &self.caller,
-                &reward_token_id,
-                &self.staking_module_type,
-                None,
-            );
+    fn secure_rewards(&self, secured_modules: &mut ManagedVec<C::Api, u8>) {
+        self.secure_module_rewards_once(&StakingModuleType::All, secured_modules);
+        self.secure_module_rewards_once(&self.staking_module_type, secured_modules);
+
+        // snakes & shares SFTs also update the SharesSfts score as a side effect
+        if self.staking_module_type == StakingModuleType::SnakesSfts
+            || self.staking_module_type == StakingModuleType::SharesSfts
+        {
+            self.secure_module_rewards_once(&StakingModuleType::SharesSfts, secured_modules);
         }
     }
 
+    fn secure_module_rewards_once(
+        &self,
+        staking_module_type: &StakingModuleType,
+        secured_modules: &mut ManagedVec<C::Api, u8>,
+    ) {
+        let module_id = staking_module_type.clone() as u8;
+        if secured_modules.contains(&module_id) {
+            return;
+        }
+
+        secure_module_rewards(self.sc_ref, &self.caller, staking_module_type);
+        secured_modules.push(module_id);
+    }
+
     fn update_primary_score(&self) {
         self.update_score(
             &StakingModuleType::All,
@@ -154,6 +224,13 @@ where
         let new_user_score =
             aggregated_user_score_with_deb + &new_pool_user_score - initial_pool_user_score;
 
+        update_unsettled_score(
+            self.sc_ref,
+            module_type,
+            &self.caller,
+            aggregated_user_score_with_deb,
+            &new_user_score,
+        );
         self.sc_ref
             .aggregated_user_staking_score(module_type, &self.caller)
             .set(new_user_score);
@@ -199,6 +276,47 @@ where
         self.update_secondary_score();
     }
 
+    pub fn get_event_data(
+        &self,
+        items: ManagedVec<C::Api, NonceQtyPair<C::Api>>,
+    ) -> StakingEventData<C::Api> {
+        StakingEventData {
+            items,
+            staking_module_type: self.staking_module_type.clone(),
+            primary_score_update: self.get_score_update(
+                &StakingModuleType::All,
+                &self.aggregated_user_score_with_deb,
+            ),
+            pool_score_update: self.get_score_update(
+                &self.staking_module_type,
+                &self.secondary_aggregated_user_score_with_deb,
+            ),
+            shares_score_update: self.initial_shares_user_score.as_ref().map(
+                |initial_shares_user_score| {
+                    self.get_score_update(&StakingModuleType::SharesSfts, initial_shares_user_score)
+                },
+            ),
+        }
+    }
+
+    fn get_score_update(
+        &self,
+        staking_module_type: &StakingModuleType,
+        previous_user_score: &BigUint<C::Api>,
+    ) -> ScoreUpdate<C::Api> {
+        ScoreUpdate {
+            previous_user_score: previous_user_score.clone(),
+            user_score: self
+                .sc_ref
+                .aggregated_user_staking_score(staking_module_type, &self.caller)
+                .get(),
+            aggregated_score: self
+                .sc_ref
+                .aggregated_staking_score(staking_module_type)
+                .get(),
+        }
+    }
+
     pub fn get_base_user_score(&self, staking_module_type: &StakingModuleType) -> BigUint<C::Api> {
         self.staking_module_impl
             .get_base_user_score(staking_module_type)
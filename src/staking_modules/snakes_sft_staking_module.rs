@@ -4,7 +4,7 @@ use super::{
     default::DefaultStakingModule,
     staking_module_type::{StakingModuleType, VestaStakingModule},
 };
-use crate::types::start_unbonding_payload::StartUnbondingPayload;
+use crate::{types::start_unbonding_payload::StartUnbondingPayload, utils::update_unsettled_score};
 
 pub struct SnakesSftStakingModule<'a, C>
 where
@@ -134,6 +134,13 @@ where
         let new_aggregated_score =
             &self.initial_general_shares_score - &self.initial_user_shares_score + new_user_score;
 
+        update_unsettled_score(
+            self.sc_ref,
+            &StakingModuleType::SharesSfts,
+            &self.default_impl.user_address,
+            &self.initial_user_shares_score,
+            new_user_score,
+        );
         self.sc_ref
             .aggregated_staking_score(&StakingModuleType::SharesSfts)
             .set(&new_aggregated_score);
@@ -11,18 +11,28 @@ use super::{
 multiversx_sc::derive_imports!();
 
 #[derive(
-    TopEncode, TopDecode, Clone, PartialEq, Eq, TypeAbi, NestedDecode, NestedEncode, Debug,
+    TopEncode,
+    TopDecode,
+    Clone,
+    PartialEq,
+    Eq,
+    TypeAbi,
+    NestedDecode,
+    NestedEncode,
+    ManagedVecItem,
+    Debug,
 )]
+// variants are encoded by their position, new ones must be appended
 pub enum StakingModuleType {
-    Invalid = 0,
-    All = 1,
-    CodingDivisionSfts = 2,
-    Bloodshed = 3,
-    Nosferatu = 4,
-    VestaXDAO = 5,
-    SnakesSfts = 6,
-    SharesSfts = 7,
-    XBunnies = 8,
+    Invalid,
+    All,
+    CodingDivisionSfts,
+    Bloodshed,
+    Nosferatu,
+    VestaXDAO,
+    SnakesSfts,
+    SharesSfts,
+    XBunnies,
 }
 
 impl StakingModuleType {
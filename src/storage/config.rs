@@ -1,4 +1,12 @@
-use crate::staking_modules::staking_module_type::StakingModuleType;
+use crate::{
+    constants::ERR_INVALID_REWARD_TOKEN_ID,
+    staking_modules::staking_module_type::StakingModuleType,
+    types::{
+        instant_unstake_fee::{FeeDestination, InstantUnstakeFee},
+        reward_token::RewardToken,
+        vesting::VestingConfig,
+    },
+};
 multiversx_sc::imports!();
 
 #[multiversx_sc::module]
@@ -17,23 +25,141 @@ pub trait ConfigModule {
     //     reward_token_id: &TokenIdentifier,
     // ) -> UnorderedSetMapper<StakingModuleType>;
 
-    #[view(getRewardTokenIdMapping)]
+    // legacy storage, merged into the reward registry on upgrade
     #[storage_mapper("reward_token_id_mapping")]
-    fn reward_token_id_mapping(&self) -> UnorderedSetMapper<(TokenIdentifier, StakingModuleType)>;
+    fn reward_token_id_mapping(
+        &self,
+    ) -> UnorderedSetMapper<(EgldOrEsdtTokenIdentifier, StakingModuleType)>;
+
+    fn get_pool_unbonding_time_penalty(
+        &self,
+        collection_token_identifier: &TokenIdentifier,
+    ) -> u64 {
+        let pool_unbonding_time_penalty =
+            self.pool_unbonding_time_penalty(collection_token_identifier);
+        if pool_unbonding_time_penalty.is_empty() {
+            return self.unbonding_time_penalty().get();
+        }
+
+        pool_unbonding_time_penalty.get()
+    }
 
     #[view(getUnbondingTimePenalty)]
     #[storage_mapper("unbonding_time_penalty")]
     fn unbonding_time_penalty(&self) -> SingleValueMapper<u64>;
 
-    #[view(getRewardTokenIdentifiers)]
+    #[view(getPoolUnbondingTimePenalty)]
+    #[storage_mapper("pool_unbonding_time_penalty")]
+    fn pool_unbonding_time_penalty(
+        &self,
+        collection_token_identifier: &TokenIdentifier,
+    ) -> SingleValueMapper<u64>;
+
+    // legacy storage, merged into the reward registry on upgrade
     #[storage_mapper("reward_token_identifiers")]
-    fn reward_token_identifiers(&self) -> UnorderedSetMapper<TokenIdentifier>;
+    fn reward_token_identifiers(&self) -> UnorderedSetMapper<EgldOrEsdtTokenIdentifier>;
+
+    /// Reward tokens keyed by (token identifier, token nonce), the nonce being zero for fungible
+    /// tokens and EGLD.
+    #[view(getRewardRegistry)]
+    #[storage_mapper("reward_registry")]
+    fn reward_registry(
+        &self,
+    ) -> MapMapper<(EgldOrEsdtTokenIdentifier, u64), RewardToken<Self::Api>>;
+
+    fn get_reward_token(
+        &self,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+    ) -> RewardToken<Self::Api> {
+        match self
+            .reward_registry()
+            .get(&(reward_token_identifier.clone(), reward_token_nonce))
+        {
+            Some(reward_token) => reward_token,
+            None => sc_panic!(ERR_INVALID_REWARD_TOKEN_ID),
+        }
+    }
+
+    #[view(getRewardTokenGeneration)]
+    #[storage_mapper("reward_token_generation")]
+    fn reward_token_generation(
+        &self,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+    ) -> SingleValueMapper<u32>;
+
+    fn add_distributed_rewards(
+        &self,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+        amount: &BigUint,
+    ) {
+        self.reward_registry()
+            .entry((reward_token_identifier.clone(), reward_token_nonce))
+            .and_modify(|reward_token| reward_token.total_distributed += amount);
+    }
+
+    fn add_claimed_rewards(
+        &self,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+        amount: &BigUint,
+    ) {
+        self.reward_registry()
+            .entry((reward_token_identifier.clone(), reward_token_nonce))
+            .and_modify(|reward_token| reward_token.total_claimed += amount);
+    }
 
     #[view(getPrimaryRewardTokenIdentifier)]
     #[storage_mapper("primary_reward_token_identifier")]
-    fn primary_reward_token_identifier(&self) -> SingleValueMapper<TokenIdentifier>;
+    fn primary_reward_token_identifier(&self) -> SingleValueMapper<EgldOrEsdtTokenIdentifier>;
 
     #[view(getEligibleStakeTokenIdentifiers)]
     #[storage_mapper("eligible_stake_token_identifiers")]
     fn eligible_stake_token_identifiers(&self) -> UnorderedSetMapper<TokenIdentifier>;
+
+    #[view(getInstantUnstakeFee)]
+    #[storage_mapper("instant_unstake_fee")]
+    fn instant_unstake_fee(
+        &self,
+        collection_token_identifier: &TokenIdentifier,
+    ) -> SingleValueMapper<InstantUnstakeFee<Self::Api>>;
+
+    #[view(getInstantUnstakeFeeDestination)]
+    #[storage_mapper("instant_unstake_fee_destination")]
+    fn instant_unstake_fee_destination(
+        &self,
+        collection_token_identifier: &TokenIdentifier,
+    ) -> SingleValueMapper<FeeDestination>;
+
+    #[view(getTreasuryAddress)]
+    #[storage_mapper("treasury_address")]
+    fn treasury_address(&self) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(getReservoirDistributionAmount)]
+    #[storage_mapper("reservoir_distribution_amount")]
+    fn reservoir_distribution_amount(
+        &self,
+        staking_module: &StakingModuleType,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+    ) -> SingleValueMapper<BigUint>;
+
+    // shares expressed against MAX_PERCENTAGE, always summing up to it
+    #[view(getDistributionWeights)]
+    #[storage_mapper("distribution_weights")]
+    fn distribution_weights(
+        &self,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+    ) -> MapMapper<StakingModuleType, u64>;
+
+    #[view(getVestingConfig)]
+    #[storage_mapper("vesting_config")]
+    fn vesting_config(
+        &self,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+    ) -> SingleValueMapper<VestingConfig>;
 }
@@ -1,3 +1,4 @@
 pub mod config;
+pub mod pause;
 pub mod score;
 pub mod user_data;
@@ -0,0 +1,25 @@
+multiversx_sc::imports!();
+
+#[multiversx_sc::module]
+pub trait PauseStorageModule {
+    #[view(isPaused)]
+    #[storage_mapper("paused")]
+    fn paused(&self) -> SingleValueMapper<bool>;
+
+    #[view(isPoolPaused)]
+    #[storage_mapper("pool_paused")]
+    fn pool_paused(&self, collection_token_identifier: &TokenIdentifier)
+        -> SingleValueMapper<bool>;
+
+    #[view(isClaimRewardsPausable)]
+    #[storage_mapper("claim_rewards_pausable")]
+    fn claim_rewards_pausable(&self) -> SingleValueMapper<bool>;
+
+    #[view(getPausers)]
+    #[storage_mapper("pausers")]
+    fn pausers(&self) -> UnorderedSetMapper<ManagedAddress>;
+
+    #[view(isEmergencyMode)]
+    #[storage_mapper("emergency_mode")]
+    fn emergency_mode(&self) -> SingleValueMapper<bool>;
+}
@@ -1,6 +1,11 @@
 use crate::{
+    constants::ERR_LEGACY_REWARDS_NOT_SETTLED,
     staking_modules::staking_module_type::StakingModuleType,
-    types::{nonce_qty_pair::NonceQtyPair, start_unbonding_payload::StartUnbondingPayload},
+    types::{
+        nonce_qty_pair::NonceQtyPair, reward_schedule::RewardSchedule,
+        start_unbonding_payload::StartUnbondingPayload, unbonding_batch::UnbondingBatch,
+        unsettled_score::UnsettledScore, vesting::VestingSchedule,
+    },
 };
 
 multiversx_sc::imports!();
@@ -24,6 +29,28 @@ pub trait UserDataStorageModule {
         staked_nfts
     }
 
+    fn get_reward_receiver(&self, address: &ManagedAddress) -> ManagedAddress {
+        let reward_receiver = self.reward_receiver(address);
+        if reward_receiver.is_empty() {
+            return address.clone();
+        }
+
+        reward_receiver.get()
+    }
+
+    #[view(getRewardReceiver)]
+    #[storage_mapper("reward_receiver")]
+    fn reward_receiver(&self, address: &ManagedAddress) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(getVestingSchedule)]
+    #[storage_mapper("vesting_schedule")]
+    fn vesting_schedule(
+        &self,
+        address: &ManagedAddress,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+    ) -> SingleValueMapper<VestingSchedule<Self::Api>>;
+
     #[view(getStakedNfts)]
     #[storage_mapper("staked_nfts")]
     fn staked_nfts(
@@ -32,6 +59,14 @@ pub trait UserDataStorageModule {
         token_identifier: &TokenIdentifier,
     ) -> MapMapper<u64, BigUint>;
 
+    fn get_next_unbonding_batch_id(&self, address: &ManagedAddress) -> u64 {
+        self.last_unbonding_batch_id(address).update(|batch_id| {
+            *batch_id += 1;
+            *batch_id
+        })
+    }
+
+    // legacy storage, batches keyed by the block timestamp they were created in
     #[view(getUnbondingAssets)]
     #[storage_mapper("unbonding_assets")]
     fn unbonding_assets(
@@ -39,6 +74,17 @@ pub trait UserDataStorageModule {
         address: &ManagedAddress,
     ) -> MapMapper<u64, StartUnbondingPayload<Self::Api>>;
 
+    #[view(getUnbondingBatches)]
+    #[storage_mapper("unbonding_batches")]
+    fn unbonding_batches(
+        &self,
+        address: &ManagedAddress,
+    ) -> MapMapper<u64, UnbondingBatch<Self::Api>>;
+
+    #[view(getLastUnbondingBatchId)]
+    #[storage_mapper("last_unbonding_batch_id")]
+    fn last_unbonding_batch_id(&self, address: &ManagedAddress) -> SingleValueMapper<u64>;
+
     #[view(getUserDeb)]
     #[storage_mapper("user_deb")]
     fn user_deb(&self, address: &ManagedAddress) -> SingleValueMapper<BigUint>;
@@ -66,12 +112,55 @@ pub trait UserDataStorageModule {
         address: &ManagedAddress,
     ) -> SingleValueMapper<BigUint>;
 
+    #[view(getUnsettledAggregatedScore)]
+    #[storage_mapper("unsettled_aggregated_score")]
+    fn unsettled_aggregated_score(
+        &self,
+        staking_module: &StakingModuleType,
+    ) -> SingleValueMapper<UnsettledScore<Self::Api>>;
+
+    #[view(getUnsettledUserScore)]
+    #[storage_mapper("unsettled_user_score")]
+    fn unsettled_user_score(
+        &self,
+        staking_module: &StakingModuleType,
+        address: &ManagedAddress,
+    ) -> SingleValueMapper<UnsettledScore<Self::Api>>;
+
+    // legacy storage, rewards stored before they were keyed by token nonce
+    #[storage_mapper("pending_rewards")]
+    fn legacy_pending_rewards(
+        &self,
+        address: &ManagedAddress,
+        token_identifier: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<BigUint>;
+
     #[view(getPendingRewards)]
     #[storage_mapper("pending_rewards")]
     fn pending_rewards(
         &self,
         address: &ManagedAddress,
-        token_identifier: &TokenIdentifier,
+        token_identifier: &EgldOrEsdtTokenIdentifier,
+        token_nonce: u64,
+    ) -> SingleValueMapper<BigUint>;
+
+    /// Fraction of a reward unit owed to the user, scaled by the precision of the reward token.
+    #[view(getPendingRewardRemainder)]
+    #[storage_mapper("pending_reward_remainder")]
+    fn pending_reward_remainder(
+        &self,
+        address: &ManagedAddress,
+        token_identifier: &EgldOrEsdtTokenIdentifier,
+        token_nonce: u64,
+    ) -> SingleValueMapper<BigUint>;
+
+    /// Sum of the reward remainders of every user, dust that can never be claimed.
+    #[view(getTotalRewardRemainder)]
+    #[storage_mapper("total_reward_remainder")]
+    fn total_reward_remainder(
+        &self,
+        token_identifier: &EgldOrEsdtTokenIdentifier,
+        token_nonce: u64,
     ) -> SingleValueMapper<BigUint>;
 
     #[view(getLastClaimedEpoch)]
@@ -88,14 +177,211 @@ pub trait UserDataStorageModule {
         &self,
         epoch: u64,
         staking_module: &StakingModuleType,
-        reward_token_identifier: &TokenIdentifier,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<BigUint>;
+
+    /// Accumulated reward per unit of score, scaled by the precision of the reward token.
+    #[view(getRewardPerShare)]
+    #[storage_mapper("reward_per_share")]
+    fn reward_per_share(
+        &self,
+        staking_module: &StakingModuleType,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getLastDistributionEpoch)]
+    #[storage_mapper("last_distribution_epoch")]
+    fn last_distribution_epoch(
+        &self,
+        staking_module: &StakingModuleType,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+    ) -> SingleValueMapper<u64>;
+
+    /// `reward_per_share` once the distributions of the epoch were done.
+    /// Only recorded for epochs that had distributions.
+    #[view(getEpochEndRewardPerShare)]
+    #[storage_mapper("epoch_end_reward_per_share")]
+    fn epoch_end_reward_per_share(
+        &self,
+        epoch: u64,
+        staking_module: &StakingModuleType,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+    ) -> SingleValueMapper<BigUint>;
+
+    /// Generation of the reward token the user reward data belongs to.
+    #[view(getUserRewardTokenGeneration)]
+    #[storage_mapper("user_reward_token_generation")]
+    fn user_reward_token_generation(
+        &self,
+        address: &ManagedAddress,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+    ) -> SingleValueMapper<u32>;
+
+    #[view(getUserRewardPerShare)]
+    #[storage_mapper("user_reward_per_share")]
+    fn user_reward_per_share(
+        &self,
+        staking_module: &StakingModuleType,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+        address: &ManagedAddress,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getUndistributedRewards)]
+    #[storage_mapper("undistributed_rewards")]
+    fn undistributed_rewards(
+        &self,
+        staking_module: &StakingModuleType,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+    ) -> SingleValueMapper<BigUint>;
+
+    /// Rewards distributed while the staking module had no score, released by the next distribution.
+    #[view(getZeroScoreReserve)]
+    #[storage_mapper("zero_score_reserve")]
+    fn zero_score_reserve(
+        &self,
+        staking_module: &StakingModuleType,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getRewardSchedule)]
+    #[storage_mapper("reward_schedule")]
+    fn reward_schedule(
+        &self,
+        staking_module: &StakingModuleType,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+    ) -> SingleValueMapper<RewardSchedule<Self::Api>>;
+
+    #[view(getRewardReservoir)]
+    #[storage_mapper("reward_reservoir")]
+    fn reward_reservoir(
+        &self,
+        staking_module: &StakingModuleType,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getLastTriggeredDistributionEpoch)]
+    #[storage_mapper("last_triggered_distribution_epoch")]
+    fn last_triggered_distribution_epoch(
+        &self,
+        staking_module: &StakingModuleType,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
+    ) -> SingleValueMapper<u64>;
+
+    /// Division remainder of past distributions, rolled into the next distribution.
+    #[view(getRewardDust)]
+    #[storage_mapper("reward_dust")]
+    fn reward_dust(
+        &self,
+        staking_module: &StakingModuleType,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        reward_token_nonce: u64,
     ) -> SingleValueMapper<BigUint>;
 
+    /// Last epoch whose rewards are only tracked through `reward_rate`.
+    /// Set when upgrading from the per-epoch reward model; empty on fresh deployments.
+    #[view(getLegacyRewardEpochEnd)]
+    #[storage_mapper("legacy_reward_epoch_end")]
+    fn legacy_reward_epoch_end(&self) -> SingleValueMapper<u64>;
+
+    /// Tokens paid out through `reward_rate` before the upgrade. Rewards of their unsettled
+    /// legacy positions are not part of `total_distributed` yet.
+    #[view(getLegacyRewardTokens)]
+    #[storage_mapper("legacy_reward_tokens")]
+    fn legacy_reward_tokens(&self) -> UnorderedSetMapper<EgldOrEsdtTokenIdentifier>;
+
+    /// Last epoch of the per-epoch model settled for the position, `legacy_reward_epoch_end` once
+    /// it moved to the cumulative model.
+    #[view(getLegacyRewardSettledEpoch)]
+    #[storage_mapper("legacy_reward_settled_epoch")]
+    fn legacy_reward_settled_epoch(
+        &self,
+        staking_module: &StakingModuleType,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        address: &ManagedAddress,
+    ) -> SingleValueMapper<u64>;
+
+    /// First and last epoch of the per-epoch model still to be settled for the position.
+    fn get_unsettled_legacy_reward_epochs(
+        &self,
+        staking_module: &StakingModuleType,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        address: &ManagedAddress,
+    ) -> Option<(u64, u64)> {
+        if self.legacy_reward_epoch_end().is_empty() {
+            return None;
+        }
+
+        let legacy_reward_epoch_end = self.legacy_reward_epoch_end().get();
+        let first_epoch = core::cmp::max(
+            self.last_claimed_epoch(staking_module, address).get(),
+            self.legacy_reward_settled_epoch(staking_module, reward_token_identifier, address)
+                .get(),
+        ) + 1;
+        if first_epoch > legacy_reward_epoch_end {
+            return None;
+        }
+
+        Some((first_epoch, legacy_reward_epoch_end))
+    }
+
+    fn require_legacy_rewards_settled(
+        &self,
+        staking_module: &StakingModuleType,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        address: &ManagedAddress,
+    ) {
+        require!(
+            self.get_unsettled_legacy_reward_epochs(
+                staking_module,
+                reward_token_identifier,
+                address
+            )
+            .is_none(),
+            ERR_LEGACY_REWARDS_NOT_SETTLED
+        );
+    }
+
+    /// Marks the position as moved to the cumulative model, no-op on fresh deployments.
+    fn set_legacy_rewards_settled(
+        &self,
+        staking_module: &StakingModuleType,
+        reward_token_identifier: &EgldOrEsdtTokenIdentifier,
+        address: &ManagedAddress,
+    ) {
+        if self.legacy_reward_epoch_end().is_empty() {
+            return;
+        }
+
+        self.legacy_reward_settled_epoch(staking_module, reward_token_identifier, address)
+            .set(self.legacy_reward_epoch_end().get());
+    }
+
+    /// Block timestamp of the latest distribution of the token in the given epoch.
+    /// Kept for readers of the per-epoch model, `reward_distribution_timestamps` lists them all.
     #[view(getRewardDistributionTimestamp)]
     #[storage_mapper("reward_distribution_timestamp")]
     fn reward_distribution_timestamp(
         &self,
         epoch: u64,
-        token_identifier: &TokenIdentifier,
+        token_identifier: &EgldOrEsdtTokenIdentifier,
     ) -> SingleValueMapper<u64>;
+
+    /// Block timestamps of every distribution of the token in the given epoch.
+    #[view(getRewardDistributionTimestamps)]
+    #[storage_mapper("reward_distribution_timestamps")]
+    fn reward_distribution_timestamps(
+        &self,
+        epoch: u64,
+        token_identifier: &EgldOrEsdtTokenIdentifier,
+    ) -> VecMapper<u64>;
 }
@@ -0,0 +1,55 @@
+use crate::staking_modules::staking_module_type::StakingModuleType;
+
+use super::nonce_qty_pair::NonceQtyPair;
+
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone, PartialEq, Eq)]
+pub struct ScoreUpdate<M: ManagedTypeApi> {
+    pub previous_user_score: BigUint<M>,
+    pub user_score: BigUint<M>,
+    pub aggregated_score: BigUint<M>,
+}
+
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone, PartialEq, Eq)]
+pub struct StakingEventData<M: ManagedTypeApi> {
+    pub items: ManagedVec<M, NonceQtyPair<M>>,
+    pub staking_module_type: StakingModuleType,
+    pub primary_score_update: ScoreUpdate<M>,
+    pub pool_score_update: ScoreUpdate<M>,
+    // snakes SFTs also change the SharesSfts score of the user
+    pub shares_score_update: Option<ScoreUpdate<M>>,
+}
+
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone, PartialEq, Eq)]
+pub struct ForceUnstakeEventData<M: ManagedTypeApi> {
+    pub payments: ManagedVec<M, EsdtTokenPayment<M>>,
+    // absent when only unbonding batches were returned, the scores did not change then
+    pub staking_event_data: Option<StakingEventData<M>>,
+}
+
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone, PartialEq, Eq)]
+pub struct DistributionEventData<M: ManagedTypeApi> {
+    pub payment: EgldOrEsdtTokenPayment<M>,
+    pub undistributed_rewards: BigUint<M>,
+    pub released_reserve: BigUint<M>,
+    pub total_score: BigUint<M>,
+    pub reward_rate: BigUint<M>,
+    pub reward_dust: BigUint<M>,
+}
+
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone, PartialEq, Eq)]
+pub struct DebUpdateEventData<M: ManagedTypeApi> {
+    pub previous_deb: BigUint<M>,
+    pub deb: BigUint<M>,
+    pub score_update: ScoreUpdate<M>,
+}
+
+#[derive(
+    TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, ManagedVecItem, Clone, PartialEq, Eq,
+)]
+pub struct DistributionWeight {
+    pub staking_module_type: StakingModuleType,
+    pub weight: u64,
+}
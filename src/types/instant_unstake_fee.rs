@@ -0,0 +1,16 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+#[derive(TopEncode, TopDecode, Clone, PartialEq, Eq, TypeAbi, NestedDecode, NestedEncode)]
+pub enum InstantUnstakeFee<M: ManagedTypeApi> {
+    // fixed fungible ESDT amount paid along with the instantUnstake call
+    Payment(EsdtTokenPayment<M>),
+}
+
+#[derive(
+    TopEncode, TopDecode, Clone, PartialEq, Eq, TypeAbi, NestedDecode, NestedEncode, Debug,
+)]
+pub enum FeeDestination {
+    Treasury,
+    Stakers,
+}
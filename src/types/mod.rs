@@ -1,3 +1,11 @@
+pub mod event_data;
+pub mod instant_unstake_fee;
 pub mod nonce_qty_pair;
+pub mod reward_payment;
+pub mod reward_schedule;
+pub mod reward_token;
 pub mod start_unbonding_payload;
 pub mod ui_types;
+pub mod unbonding_batch;
+pub mod unsettled_score;
+pub mod vesting;
@@ -0,0 +1,26 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+// same layout as `EgldOrEsdtTokenPayment`, which cannot be stored in a `ManagedVec`
+#[derive(
+    TopEncode, TopDecode, Clone, PartialEq, Eq, TypeAbi, ManagedVecItem, NestedDecode, NestedEncode,
+)]
+pub struct RewardPayment<M: ManagedTypeApi> {
+    pub token_identifier: EgldOrEsdtTokenIdentifier<M>,
+    pub token_nonce: u64,
+    pub amount: BigUint<M>,
+}
+
+impl<M: ManagedTypeApi> RewardPayment<M> {
+    pub fn new(
+        token_identifier: EgldOrEsdtTokenIdentifier<M>,
+        token_nonce: u64,
+        amount: BigUint<M>,
+    ) -> Self {
+        Self {
+            token_identifier,
+            token_nonce,
+            amount,
+        }
+    }
+}
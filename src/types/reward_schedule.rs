@@ -0,0 +1,43 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone, PartialEq, Eq)]
+pub struct RewardSchedule<M: ManagedTypeApi> {
+    pub amount_per_epoch: BigUint<M>,
+    pub remaining_amount: BigUint<M>,
+    pub last_release_epoch: u64,
+    pub end_epoch: u64,
+}
+
+impl<M: ManagedTypeApi> RewardSchedule<M> {
+    pub fn new(amount: BigUint<M>, epochs: u64, start_epoch: u64) -> Self {
+        Self {
+            amount_per_epoch: &amount / epochs,
+            remaining_amount: amount,
+            last_release_epoch: start_epoch,
+            end_epoch: start_epoch + epochs,
+        }
+    }
+
+    /// Returns the amount due for the epochs elapsed since the last release.
+    /// The last epoch also releases the division remainder.
+    pub fn release(&mut self, epoch: u64) -> BigUint<M> {
+        if epoch <= self.last_release_epoch {
+            return BigUint::zero();
+        }
+
+        let released_amount = match epoch >= self.end_epoch {
+            true => self.remaining_amount.clone(),
+            false => &self.amount_per_epoch * (epoch - self.last_release_epoch),
+        };
+        self.remaining_amount -= &released_amount;
+        self.last_release_epoch = epoch;
+
+        released_amount
+    }
+
+    pub fn get_remaining_epochs(&self, epoch: u64) -> u64 {
+        self.end_epoch
+            .saturating_sub(epoch.max(self.last_release_epoch))
+    }
+}
@@ -0,0 +1,67 @@
+use crate::staking_modules::staking_module_type::StakingModuleType;
+
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone, PartialEq, Eq)]
+pub enum RewardTokenStatus {
+    Active,
+    // no more distributions, users keep claiming until the token is swept
+    Retired,
+}
+
+/// Registry entry of a reward token, the single source of truth for settling its rewards.
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone, PartialEq, Eq)]
+pub struct RewardToken<M: ManagedTypeApi> {
+    pub token_identifier: EgldOrEsdtTokenIdentifier<M>,
+    // nonce of the SFT paid out as reward, zero for fungible reward tokens
+    pub token_nonce: u64,
+    // staking modules the token can be distributed to and settled in
+    pub staking_modules: ManagedVec<M, StakingModuleType>,
+    pub status: RewardTokenStatus,
+    pub sweep_deadline: u64,
+    // scale of the reward per share accumulators of the token
+    pub precision: BigUint<M>,
+    pub total_distributed: BigUint<M>,
+    pub total_claimed: BigUint<M>,
+    // number of times the token was swept, user reward data of older generations is discarded
+    pub generation: u32,
+}
+
+impl<M: ManagedTypeApi> RewardToken<M> {
+    pub fn new(
+        token_identifier: EgldOrEsdtTokenIdentifier<M>,
+        token_nonce: u64,
+        precision: u64,
+        generation: u32,
+    ) -> Self {
+        Self {
+            token_identifier,
+            token_nonce,
+            staking_modules: ManagedVec::new(),
+            status: RewardTokenStatus::Active,
+            sweep_deadline: 0,
+            precision: BigUint::from(precision),
+            total_distributed: BigUint::zero(),
+            total_claimed: BigUint::zero(),
+            generation,
+        }
+    }
+
+    pub fn targets(&self, staking_module: &StakingModuleType) -> bool {
+        self.staking_modules.contains(staking_module)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.status == RewardTokenStatus::Active
+    }
+
+    /// Distributed rewards that were not claimed yet, tracked since the registry was introduced.
+    pub fn get_unclaimed_amount(&self) -> BigUint<M> {
+        if self.total_claimed >= self.total_distributed {
+            return BigUint::zero();
+        }
+
+        &self.total_distributed - &self.total_claimed
+    }
+}
@@ -1,4 +1,7 @@
-use super::{nonce_qty_pair::NonceQtyPair, start_unbonding_payload::StartUnbondingPayload};
+use super::{
+    nonce_qty_pair::NonceQtyPair, reward_payment::RewardPayment,
+    start_unbonding_payload::StartUnbondingPayload,
+};
 
 multiversx_sc::derive_imports!();
 multiversx_sc::imports!();
@@ -35,7 +38,9 @@ pub struct UIUserPoolData<M: ManagedTypeApi> {
     TopEncode, TopDecode, Clone, PartialEq, Eq, TypeAbi, ManagedVecItem, NestedDecode, NestedEncode,
 )]
 pub struct UIUnbondingAsset<M: ManagedTypeApi> {
-    pub timestamp: u64,
+    pub batch_id: u64,
+    pub start_timestamp: u64,
+    pub release_timestamp: u64,
     pub assets: StartUnbondingPayload<M>,
 }
 
@@ -43,7 +48,7 @@ pub struct UIUnbondingAsset<M: ManagedTypeApi> {
     TopEncode, TopDecode, Clone, PartialEq, Eq, TypeAbi, ManagedVecItem, NestedDecode, NestedEncode,
 )]
 pub struct UIUserDataPayload<M: ManagedTypeApi> {
-    pub pending_rewards: ManagedVec<M, EsdtTokenPayment<M>>,
+    pub pending_rewards: ManagedVec<M, RewardPayment<M>>,
     pub user_pool_data: ManagedVec<M, UIUserPoolData<M>>,
     pub unbonding_assets: ManagedVec<M, UIUnbondingAsset<M>>,
     pub user_deb: BigUint<M>,
@@ -0,0 +1,35 @@
+use super::start_unbonding_payload::StartUnbondingPayload;
+
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+#[derive(
+    TopEncode, TopDecode, Clone, PartialEq, Eq, TypeAbi, ManagedVecItem, NestedDecode, NestedEncode,
+)]
+pub struct UnbondingBatch<M: ManagedTypeApi> {
+    pub payload: StartUnbondingPayload<M>,
+    pub start_timestamp: u64,
+    pub release_timestamp: u64,
+}
+
+impl<M: ManagedTypeApi> UnbondingBatch<M> {
+    pub fn new(
+        payload: StartUnbondingPayload<M>,
+        start_timestamp: u64,
+        unbonding_time: u64,
+    ) -> Self {
+        Self {
+            payload,
+            start_timestamp,
+            release_timestamp: start_timestamp + unbonding_time,
+        }
+    }
+
+    pub fn token_identifier(&self) -> &TokenIdentifier<M> {
+        &self.payload.token_identifier
+    }
+
+    pub fn is_unbonded(&self, block_timestamp: u64) -> bool {
+        self.release_timestamp <= block_timestamp
+    }
+}
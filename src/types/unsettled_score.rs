@@ -0,0 +1,26 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+/// Score added during `epoch`. It only earns the rewards distributed after that epoch ends.
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone, PartialEq, Eq)]
+pub struct UnsettledScore<M: ManagedTypeApi> {
+    pub epoch: u64,
+    pub amount: BigUint<M>,
+}
+
+impl<M: ManagedTypeApi> UnsettledScore<M> {
+    pub fn new(epoch: u64) -> Self {
+        Self {
+            epoch,
+            amount: BigUint::zero(),
+        }
+    }
+
+    /// Returns the score that is still unsettled in the given epoch.
+    pub fn get_amount(&self, epoch: u64) -> BigUint<M> {
+        match self.epoch == epoch {
+            true => self.amount.clone(),
+            false => BigUint::zero(),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::staking_modules::staking_module_type::StakingModuleType;
+
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone, PartialEq, Eq)]
+pub struct VestingConfig {
+    pub vesting_epochs: u64,
+    // share of the locked amount kept on early withdrawal, expressed against MAX_PERCENTAGE
+    // zero disables early withdrawal
+    pub early_withdraw_penalty: u64,
+    pub penalty_staking_module: StakingModuleType,
+}
+
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone, PartialEq, Eq)]
+pub struct VestingSchedule<M: ManagedTypeApi> {
+    pub unlocked_amount: BigUint<M>,
+    pub locked_amount: BigUint<M>,
+    pub last_update_epoch: u64,
+    pub end_epoch: u64,
+}
+
+impl<M: ManagedTypeApi> VestingSchedule<M> {
+    pub fn new(epoch: u64) -> Self {
+        Self {
+            unlocked_amount: BigUint::zero(),
+            locked_amount: BigUint::zero(),
+            last_update_epoch: epoch,
+            end_epoch: epoch,
+        }
+    }
+
+    /// Unlocks the amount vested linearly since the last update.
+    pub fn update(&mut self, epoch: u64) {
+        if epoch <= self.last_update_epoch {
+            return;
+        }
+
+        let unlocked_amount = match epoch >= self.end_epoch {
+            true => self.locked_amount.clone(),
+            false => {
+                &self.locked_amount * (epoch - self.last_update_epoch)
+                    / (self.end_epoch - self.last_update_epoch)
+            }
+        };
+        self.locked_amount -= &unlocked_amount;
+        self.unlocked_amount += unlocked_amount;
+        self.last_update_epoch = epoch;
+    }
+
+    /// Locks a new amount vesting over `vesting_epochs` from now. The end epoch becomes the
+    /// average of the remaining and new vesting periods weighted by their amounts, rounded up,
+    /// so amounts locked earlier are not pushed back to the end of the new period.
+    pub fn lock(&mut self, amount: &BigUint<M>, epoch: u64, vesting_epochs: u64) {
+        self.update(epoch);
+        let remaining_epochs = self.end_epoch.saturating_sub(epoch);
+        let total_locked_amount = &self.locked_amount + amount;
+        if total_locked_amount == 0 {
+            return;
+        }
+
+        let weighted_epochs =
+            &self.locked_amount * remaining_epochs + amount * vesting_epochs + &total_locked_amount
+                - 1u64;
+        let average_epochs = (weighted_epochs / &total_locked_amount)
+            .to_u64()
+            .unwrap_or(vesting_epochs);
+        self.locked_amount = total_locked_amount;
+        self.end_epoch = epoch + average_epochs;
+    }
+
+    pub fn take_unlocked(&mut self, epoch: u64) -> BigUint<M> {
+        self.update(epoch);
+        core::mem::take(&mut self.unlocked_amount)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.unlocked_amount == 0 && self.locked_amount == 0
+    }
+}
@@ -1,47 +1,107 @@
-use crate::staking_modules::staking_module_type::StakingModuleType;
+use crate::{
+    constants::{MAX_LEGACY_SETTLEMENT_EPOCHS, REWARD_PER_SHARE_PRECISION},
+    staking_modules::staking_module_type::StakingModuleType,
+    types::{
+        event_data::DistributionEventData, reward_payment::RewardPayment,
+        reward_token::RewardToken, unbonding_batch::UnbondingBatch,
+        unsettled_score::UnsettledScore,
+    },
+};
 
 multiversx_sc::imports!();
 
-pub fn claim_all_pending_rewards<'a, C>(
-    sc_ref: &'a C,
+pub fn claim_all_pending_rewards<C>(
+    sc_ref: &C,
     caller: &ManagedAddress<C::Api>,
-) -> ManagedVec<C::Api, EsdtTokenPayment<C::Api>>
+) -> ManagedVec<C::Api, RewardPayment<C::Api>>
 where
     C: crate::storage::config::ConfigModule,
     C: crate::storage::user_data::UserDataStorageModule,
     C: crate::storage::score::ScoreStorageModule,
+    C: crate::events::EventsModule,
+    C: crate::storage::pause::PauseStorageModule,
 {
     let pending_rewards = get_all_pending_rewards(sc_ref, caller, true);
 
     for pending_reward in pending_rewards.iter() {
         sc_ref
-            .pending_rewards(caller, &pending_reward.token_identifier)
+            .pending_rewards(
+                caller,
+                &pending_reward.token_identifier,
+                pending_reward.token_nonce,
+            )
             .clear();
+        sc_ref.add_claimed_rewards(
+            &pending_reward.token_identifier,
+            pending_reward.token_nonce,
+            &pending_reward.amount,
+        );
     }
 
     pending_rewards
 }
 
-pub fn get_all_pending_rewards<'a, C>(
-    sc_ref: &'a C,
+pub fn claim_token_pending_rewards<C>(
+    sc_ref: &C,
+    caller: &ManagedAddress<C::Api>,
+    token_identifiers: &ManagedVec<C::Api, EgldOrEsdtTokenIdentifier<C::Api>>,
+) -> ManagedVec<C::Api, RewardPayment<C::Api>>
+where
+    C: crate::storage::config::ConfigModule,
+    C: crate::storage::user_data::UserDataStorageModule,
+    C: crate::storage::score::ScoreStorageModule,
+    C: crate::events::EventsModule,
+    C: crate::storage::pause::PauseStorageModule,
+{
+    let mut pending_rewards = ManagedVec::new();
+    for reward_token in sc_ref.reward_registry().values() {
+        if !token_identifiers.contains(&reward_token.token_identifier) {
+            continue;
+        }
+
+        if let Some(pending_reward) =
+            get_single_token_pending_reward_payment(sc_ref, caller, &reward_token, true)
+        {
+            pending_rewards.push(pending_reward);
+        }
+    }
+
+    for pending_reward in pending_rewards.iter() {
+        sc_ref
+            .pending_rewards(
+                caller,
+                &pending_reward.token_identifier,
+                pending_reward.token_nonce,
+            )
+            .clear();
+        sc_ref.add_claimed_rewards(
+            &pending_reward.token_identifier,
+            pending_reward.token_nonce,
+            &pending_reward.amount,
+        );
+    }
+
+    pending_rewards
+}
+
+pub fn get_all_pending_rewards<C>(
+    sc_ref: &C,
     address: &ManagedAddress<C::Api>,
     store_rewards: bool,
-) -> ManagedVec<C::Api, EsdtTokenPayment<C::Api>>
+) -> ManagedVec<C::Api, RewardPayment<C::Api>>
 where
     C: crate::storage::config::ConfigModule,
     C: crate::storage::user_data::UserDataStorageModule,
     C: crate::storage::score::ScoreStorageModule,
+    C: crate::events::EventsModule,
+    C: crate::storage::pause::PauseStorageModule,
 {
     let mut pending_rewards = ManagedVec::new();
 
-    for (token_identifier, staking_module_type) in sc_ref.reward_token_id_mapping().iter() {
-        if let Some(pending_reward) = get_single_token_pending_reward_payment(
-            sc_ref,
-            address,
-            &token_identifier,
-            store_rewards,
-            &staking_module_type,
-        ) {
+    for reward_token in sc_ref.reward_registry().values() {
+        if let Some(pending_reward) =
+            get_single_token_pending_reward_payment(sc_ref, address, &reward_token, store_rewards)
+        {
             pending_rewards.push(pending_reward);
         }
     }
@@ -49,98 +109,411 @@ where
     pending_rewards
 }
 
-pub fn get_single_token_pending_reward_payment<'a, C>(
-    sc_ref: &'a C,
+/// Pending reward of a token, settled across every staking module the token targets.
+pub fn get_single_token_pending_reward_payment<C>(
+    sc_ref: &C,
     address: &ManagedAddress<C::Api>,
-    token_identifier: &TokenIdentifier<C::Api>,
+    reward_token: &RewardToken<C::Api>,
     store_rewards: bool,
-    staking_module_type: &StakingModuleType,
-) -> Option<EsdtTokenPayment<C::Api>>
+) -> Option<RewardPayment<C::Api>>
 where
     C: crate::storage::config::ConfigModule,
     C: crate::storage::user_data::UserDataStorageModule,
     C: crate::storage::score::ScoreStorageModule,
+    C: crate::events::EventsModule,
+    C: crate::storage::pause::PauseStorageModule,
 {
-    let pending_reward =
-        get_total_token_pending_reward(sc_ref, address, token_identifier, staking_module_type);
-    if &pending_reward == &0 {
+    let token_identifier = &reward_token.token_identifier;
+    let pending_reward = match store_rewards {
+        true => {
+            for staking_module in reward_token.staking_modules.iter() {
+                secure_rewards(sc_ref, address, reward_token, &staking_module);
+            }
+            sc_ref
+                .pending_rewards(address, token_identifier, reward_token.token_nonce)
+                .get()
+        }
+        false => get_total_token_pending_reward(sc_ref, address, reward_token),
+    };
+    if pending_reward == 0 {
         return None;
     }
 
-    if store_rewards {
-        secure_rewards(
-            sc_ref,
-            address,
-            &token_identifier,
-            &staking_module_type,
-            Some(pending_reward.clone()),
-        );
-    }
-
-    Some(EsdtTokenPayment::new(
+    Some(RewardPayment::new(
         token_identifier.clone(),
-        0,
+        reward_token.token_nonce,
         pending_reward,
     ))
 }
 
-pub fn secure_rewards<'a, C>(
-    sc_ref: &'a C,
+pub fn secure_rewards<C>(
+    sc_ref: &C,
     address: &ManagedAddress<C::Api>,
-    token_identifier: &TokenIdentifier<C::Api>,
+    reward_token: &RewardToken<C::Api>,
     staking_module: &StakingModuleType,
-    pending_rewards_opt: Option<BigUint<C::Api>>,
 ) where
     C: crate::storage::config::ConfigModule,
     C: crate::storage::user_data::UserDataStorageModule,
     C: crate::storage::score::ScoreStorageModule,
+    C: crate::events::EventsModule,
+    C: crate::storage::pause::PauseStorageModule,
 {
-    let pending_rewards = match pending_rewards_opt {
-        Some(val) => val,
-        None => get_total_token_pending_reward(sc_ref, address, token_identifier, staking_module),
-    };
-    let block_epoch = sc_ref.blockchain().get_block_epoch();
-    if sc_ref
-        .reward_rate(block_epoch, staking_module, token_identifier)
-        .is_empty()
-    {
+    let token_identifier = &reward_token.token_identifier;
+    let token_nonce = reward_token.token_nonce;
+    reset_stale_user_rewards(sc_ref, address, reward_token);
+    migrate_legacy_pending_rewards(sc_ref, address, reward_token);
+    release_scheduled_rewards(sc_ref, staking_module, reward_token);
+    // the per-epoch model pays the current score, which must not change before it is settled
+    settle_legacy_rewards(
+        sc_ref,
+        address,
+        reward_token,
+        staking_module,
+        MAX_LEGACY_SETTLEMENT_EPOCHS,
+    );
+    sc_ref.require_legacy_rewards_settled(staking_module, token_identifier, address);
+    let (unstored_rewards, reward_remainder) =
+        get_unstored_pending_rewards(sc_ref, address, reward_token, staking_module);
+    let reward_per_share = sc_ref
+        .reward_per_share(staking_module, token_identifier, token_nonce)
+        .get();
+
+    sc_ref
+        .user_reward_per_share(staking_module, token_identifier, token_nonce, address)
+        .set(reward_per_share);
+    sc_ref
+        .pending_rewards(address, token_identifier, token_nonce)
+        .update(|pending_rewards| *pending_rewards += unstored_rewards);
+    let old_reward_remainder = sc_ref
+        .pending_reward_remainder(address, token_identifier, token_nonce)
+        .replace(reward_remainder.clone());
+    sc_ref
+        .total_reward_remainder(token_identifier, token_nonce)
+        .update(|total_reward_remainder| {
+            *total_reward_remainder += reward_remainder;
+            *total_reward_remainder -= old_reward_remainder;
+        });
+}
+
+/// Settles the rewards of every registered token that targets the staking module.
+pub fn secure_module_rewards<C>(
+    sc_ref: &C,
+    address: &ManagedAddress<C::Api>,
+    staking_module: &StakingModuleType,
+) where
+    C: crate::storage::config::ConfigModule,
+    C: crate::storage::user_data::UserDataStorageModule,
+    C: crate::storage::score::ScoreStorageModule,
+    C: crate::events::EventsModule,
+    C: crate::storage::pause::PauseStorageModule,
+{
+    for reward_token in sc_ref.reward_registry().values() {
+        if !reward_token.targets(staking_module) {
+            continue;
+        }
+
+        secure_rewards(sc_ref, address, &reward_token, staking_module);
+    }
+}
+
+/// Whether the reward data of the user was left from before the token was last swept.
+pub fn is_user_reward_stale<C>(
+    sc_ref: &C,
+    address: &ManagedAddress<C::Api>,
+    reward_token: &RewardToken<C::Api>,
+) -> bool
+where
+    C: crate::storage::user_data::UserDataStorageModule,
+{
+    sc_ref
+        .user_reward_token_generation(
+            address,
+            &reward_token.token_identifier,
+            reward_token.token_nonce,
+        )
+        .get()
+        != reward_token.generation
+}
+
+/// Discards what the user earned before the token was swept, the unclaimed rewards were sent
+/// back to the owner. Checkpoints restart from the cleared accumulators of the token.
+fn reset_stale_user_rewards<C>(
+    sc_ref: &C,
+    address: &ManagedAddress<C::Api>,
+    reward_token: &RewardToken<C::Api>,
+) where
+    C: crate::storage::user_data::UserDataStorageModule,
+{
+    if !is_user_reward_stale(sc_ref, address, reward_token) {
+        return;
+    }
+
+    let token_identifier = &reward_token.token_identifier;
+    let token_nonce = reward_token.token_nonce;
+    sc_ref
+        .pending_rewards(address, token_identifier, token_nonce)
+        .clear();
+    if token_nonce == 0 {
         sc_ref
-            .last_claimed_epoch(staking_module, address)
-            .set(&block_epoch - 1);
-    } else {
+            .legacy_pending_rewards(address, token_identifier)
+            .clear();
+    }
+    sc_ref
+        .pending_reward_remainder(address, token_identifier, token_nonce)
+        .clear();
+    for staking_module in StakingModuleType::iter() {
         sc_ref
-            .last_claimed_epoch(staking_module, address)
-            .set(block_epoch);
+            .user_reward_per_share(staking_module, token_identifier, token_nonce, address)
+            .set(BigUint::zero());
+        if token_nonce == 0 {
+            sc_ref.set_legacy_rewards_settled(staking_module, token_identifier, address);
+        }
+    }
+    sc_ref
+        .user_reward_token_generation(address, token_identifier, token_nonce)
+        .set(reward_token.generation);
+}
+
+/// Moves the pending rewards stored before they were keyed by token nonce. Only fungible tokens
+/// were paid out back then, their rewards were never counted as distributed.
+fn migrate_legacy_pending_rewards<C>(
+    sc_ref: &C,
+    address: &ManagedAddress<C::Api>,
+    reward_token: &RewardToken<C::Api>,
+) where
+    C: crate::storage::config::ConfigModule,
+    C: crate::storage::user_data::UserDataStorageModule,
+{
+    if reward_token.token_nonce != 0 {
+        return;
     }
 
+    let token_identifier = &reward_token.token_identifier;
+    let legacy_pending_rewards = sc_ref
+        .legacy_pending_rewards(address, token_identifier)
+        .take();
+    if legacy_pending_rewards == 0 {
+        return;
+    }
+
+    sc_ref.add_distributed_rewards(token_identifier, 0, &legacy_pending_rewards);
     sc_ref
-        .pending_rewards(address, token_identifier)
-        .update(|old_value| *old_value = pending_rewards);
+        .pending_rewards(address, token_identifier, 0)
+        .update(|pending_rewards| *pending_rewards += legacy_pending_rewards);
+}
+
+pub fn get_total_token_pending_reward<C>(
+    sc_ref: &C,
+    address: &ManagedAddress<C::Api>,
+    reward_token: &RewardToken<C::Api>,
+) -> BigUint<C::Api>
+where
+    C: crate::storage::config::ConfigModule,
+    C: crate::storage::user_data::UserDataStorageModule,
+    C: crate::storage::score::ScoreStorageModule,
+{
+    let token_identifier = &reward_token.token_identifier;
+    let token_nonce = reward_token.token_nonce;
+    let is_stale = is_user_reward_stale(sc_ref, address, reward_token);
+    let (mut scaled_pending_reward, mut stored_amount) = match is_stale {
+        true => (BigUint::zero(), BigUint::zero()),
+        false => (
+            sc_ref
+                .pending_reward_remainder(address, token_identifier, token_nonce)
+                .get(),
+            sc_ref
+                .pending_rewards(address, token_identifier, token_nonce)
+                .get(),
+        ),
+    };
+    if !is_stale && token_nonce == 0 {
+        stored_amount += sc_ref
+            .legacy_pending_rewards(address, token_identifier)
+            .get();
+    }
+    for staking_module in reward_token.staking_modules.iter() {
+        scaled_pending_reward += get_scaled_unstored_pending_rewards(
+            sc_ref,
+            address,
+            reward_token,
+            &staking_module,
+            is_stale,
+        ) + get_unsettled_legacy_rewards(
+            sc_ref,
+            address,
+            reward_token,
+            &staking_module,
+            is_stale,
+        ) * &reward_token.precision;
+    }
+
+    scaled_pending_reward / &reward_token.precision + stored_amount
+}
+
+/// Whole reward units earned since the user checkpoint, along with the fraction of a unit
+/// left over, scaled by the precision of the token, which is carried to the next settlement.
+pub fn get_unstored_pending_rewards<C>(
+    sc_ref: &C,
+    address: &ManagedAddress<C::Api>,
+    reward_token: &RewardToken<C::Api>,
+    staking_module: &StakingModuleType,
+) -> (BigUint<C::Api>, BigUint<C::Api>)
+where
+    C: crate::storage::config::ConfigModule,
+    C: crate::storage::user_data::UserDataStorageModule,
+    C: crate::storage::score::ScoreStorageModule,
+{
+    let scaled_pending_reward =
+        get_scaled_unstored_pending_rewards(sc_ref, address, reward_token, staking_module, false)
+            + sc_ref
+                .pending_reward_remainder(
+                    address,
+                    &reward_token.token_identifier,
+                    reward_token.token_nonce,
+                )
+                .get();
+
+    (
+        &scaled_pending_reward / &reward_token.precision,
+        scaled_pending_reward % &reward_token.precision,
+    )
 }
 
-pub fn get_total_token_pending_reward<'a, C>(
-    sc_ref: &'a C,
+/// Rewards of the per-epoch model the position has not settled yet.
+fn get_unsettled_legacy_rewards<C>(
+    sc_ref: &C,
     address: &ManagedAddress<C::Api>,
-    token_identifier: &TokenIdentifier<C::Api>,
+    reward_token: &RewardToken<C::Api>,
     staking_module: &StakingModuleType,
+    is_stale: bool,
 ) -> BigUint<C::Api>
 where
     C: crate::storage::config::ConfigModule,
     C: crate::storage::user_data::UserDataStorageModule,
     C: crate::storage::score::ScoreStorageModule,
 {
-    let not_stored_amount =
-        get_unstored_pending_rewards(sc_ref, address, token_identifier, staking_module);
-    let stored_amount = sc_ref.pending_rewards(address, token_identifier).get();
+    // the per-epoch model only paid out fungible tokens
+    if is_stale || reward_token.token_nonce != 0 {
+        return BigUint::zero();
+    }
+
+    let token_identifier = &reward_token.token_identifier;
+    let (first_epoch, last_epoch) = match sc_ref.get_unsettled_legacy_reward_epochs(
+        staking_module,
+        token_identifier,
+        address,
+    ) {
+        Some(legacy_reward_epochs) => legacy_reward_epochs,
+        None => return BigUint::zero(),
+    };
 
-    not_stored_amount + stored_amount
+    let user_score = sc_ref
+        .aggregated_user_staking_score(staking_module, address)
+        .get();
+    get_legacy_pending_rewards(
+        sc_ref,
+        token_identifier,
+        staking_module,
+        &user_score,
+        first_epoch,
+        last_epoch,
+    )
 }
 
-pub fn get_unstored_pending_rewards<'a, C>(
-    sc_ref: &'a C,
+/// Settles up to `max_epochs` epochs of the per-epoch model for the position and stores the
+/// progress. Returns the number of epochs walked.
+pub fn settle_legacy_rewards<C>(
+    sc_ref: &C,
     address: &ManagedAddress<C::Api>,
-    token_identifier: &TokenIdentifier<C::Api>,
+    reward_token: &RewardToken<C::Api>,
     staking_module: &StakingModuleType,
+    max_epochs: u64,
+) -> u64
+where
+    C: crate::storage::config::ConfigModule,
+    C: crate::storage::user_data::UserDataStorageModule,
+    C: crate::storage::score::ScoreStorageModule,
+{
+    if reward_token.token_nonce != 0 || max_epochs == 0 {
+        return 0;
+    }
+
+    reset_stale_user_rewards(sc_ref, address, reward_token);
+    let token_identifier = &reward_token.token_identifier;
+    let (first_epoch, legacy_reward_epoch_end) = match sc_ref.get_unsettled_legacy_reward_epochs(
+        staking_module,
+        token_identifier,
+        address,
+    ) {
+        Some(legacy_reward_epochs) => legacy_reward_epochs,
+        None => return 0,
+    };
+
+    // positions opened after the upgrade hold no score in the legacy epochs
+    let user_score = sc_ref
+        .aggregated_user_staking_score(staking_module, address)
+        .get();
+    if user_score == 0 {
+        sc_ref.set_legacy_rewards_settled(staking_module, token_identifier, address);
+        return 0;
+    }
+
+    let last_epoch = core::cmp::min(legacy_reward_epoch_end, first_epoch + max_epochs - 1);
+    let legacy_rewards = get_legacy_pending_rewards(
+        sc_ref,
+        token_identifier,
+        staking_module,
+        &user_score,
+        first_epoch,
+        last_epoch,
+    );
+    sc_ref
+        .legacy_reward_settled_epoch(staking_module, token_identifier, address)
+        .set(last_epoch);
+
+    // rewards of the per-epoch model were never counted as distributed
+    if legacy_rewards > 0 {
+        sc_ref.add_distributed_rewards(token_identifier, 0, &legacy_rewards);
+        sc_ref
+            .pending_rewards(address, token_identifier, 0)
+            .update(|pending_rewards| *pending_rewards += legacy_rewards);
+    }
+
+    last_epoch - first_epoch + 1
+}
+
+/// Settles up to `max_epochs` epochs of the per-epoch model across the positions of the user.
+/// Returns the epochs left to the budget.
+pub fn settle_user_legacy_rewards<C>(
+    sc_ref: &C,
+    address: &ManagedAddress<C::Api>,
+    mut max_epochs: u64,
+) -> u64
+where
+    C: crate::storage::config::ConfigModule,
+    C: crate::storage::user_data::UserDataStorageModule,
+    C: crate::storage::score::ScoreStorageModule,
+{
+    for staking_module in StakingModuleType::iter() {
+        for reward_token in sc_ref.reward_registry().values() {
+            if !reward_token.targets(staking_module) {
+                continue;
+            }
+
+            max_epochs -=
+                settle_legacy_rewards(sc_ref, address, &reward_token, staking_module, max_epochs);
+        }
+    }
+
+    max_epochs
+}
+
+fn get_scaled_unstored_pending_rewards<C>(
+    sc_ref: &C,
+    address: &ManagedAddress<C::Api>,
+    reward_token: &RewardToken<C::Api>,
+    staking_module: &StakingModuleType,
+    is_stale: bool,
 ) -> BigUint<C::Api>
 where
     C: crate::storage::config::ConfigModule,
@@ -151,26 +524,473 @@ where
         .aggregated_user_staking_score(staking_module, address)
         .get();
 
-    if &user_score == &0 {
+    if user_score == 0 {
+        return BigUint::zero();
+    }
+
+    let token_identifier = &reward_token.token_identifier;
+    let token_nonce = reward_token.token_nonce;
+    let reward_per_share = sc_ref
+        .reward_per_share(staking_module, token_identifier, token_nonce)
+        .get();
+    // checkpoints taken before the token was swept count as zero
+    let user_reward_per_share = match is_stale {
+        true => BigUint::zero(),
+        false => sc_ref
+            .user_reward_per_share(staking_module, token_identifier, token_nonce, address)
+            .get(),
+    };
+    let unsettled_score =
+        load_unsettled_score(&sc_ref.unsettled_user_score(staking_module, address));
+    let settled_score = &user_score - &unsettled_score.amount;
+    let mut pending_reward = settled_score * &(&reward_per_share - &user_reward_per_share);
+
+    // unsettled score starts earning with the first distribution after the epoch it was added in
+    if unsettled_score.amount > 0 {
+        let settlement_reward_per_share = get_settlement_reward_per_share(
+            sc_ref,
+            staking_module,
+            token_identifier,
+            token_nonce,
+            unsettled_score.epoch,
+            &reward_per_share,
+        )
+        .max(user_reward_per_share)
+        // epoch end values recorded before a sweep can exceed the restarted accumulator
+        .min(reward_per_share.clone());
+        pending_reward +=
+            &unsettled_score.amount * &(reward_per_share - settlement_reward_per_share);
+    }
+
+    pending_reward
+}
+
+fn get_settlement_reward_per_share<C>(
+    sc_ref: &C,
+    staking_module: &StakingModuleType,
+    token_identifier: &EgldOrEsdtTokenIdentifier<C::Api>,
+    token_nonce: u64,
+    epoch: u64,
+    reward_per_share: &BigUint<C::Api>,
+) -> BigUint<C::Api>
+where
+    C: crate::storage::user_data::UserDataStorageModule,
+{
+    let epoch_end_reward_per_share_mapper =
+        sc_ref.epoch_end_reward_per_share(epoch, staking_module, token_identifier, token_nonce);
+    if !epoch_end_reward_per_share_mapper.is_empty() {
+        return epoch_end_reward_per_share_mapper.get();
+    }
+
+    // the epoch is still running or nothing was distributed since it ended
+    if epoch == sc_ref.blockchain().get_block_epoch()
+        || epoch
+            == sc_ref
+                .last_distribution_epoch(staking_module, token_identifier, token_nonce)
+                .get()
+    {
+        return reward_per_share.clone();
+    }
+
+    // nothing was distributed in the epoch, the user checkpoint taken in it is already accurate
+    BigUint::zero()
+}
+
+/// Keeps track of the score a user added during the current epoch.
+/// Removed score cancels the unsettled score first.
+pub fn update_unsettled_score<C>(
+    sc_ref: &C,
+    staking_module: &StakingModuleType,
+    address: &ManagedAddress<C::Api>,
+    old_user_score: &BigUint<C::Api>,
+    new_user_score: &BigUint<C::Api>,
+) where
+    C: crate::storage::user_data::UserDataStorageModule,
+{
+    let block_epoch = sc_ref.blockchain().get_block_epoch();
+    let user_mapper = sc_ref.unsettled_user_score(staking_module, address);
+    let aggregated_mapper = sc_ref.unsettled_aggregated_score(staking_module);
+    let mut user_unsettled_score = UnsettledScore {
+        epoch: block_epoch,
+        amount: load_unsettled_score(&user_mapper).get_amount(block_epoch),
+    };
+    let mut aggregated_unsettled_score = UnsettledScore {
+        epoch: block_epoch,
+        amount: load_unsettled_score(&aggregated_mapper).get_amount(block_epoch),
+    };
+
+    if new_user_score > old_user_score {
+        let added_score = new_user_score - old_user_score;
+        user_unsettled_score.amount += &added_score;
+        aggregated_unsettled_score.amount += &added_score;
+    } else {
+        let removed_score = core::cmp::min(
+            old_user_score - new_user_score,
+            user_unsettled_score.amount.clone(),
+        );
+        user_unsettled_score.amount -= &removed_score;
+        aggregated_unsettled_score.amount =
+            saturating_sub(&aggregated_unsettled_score.amount, &removed_score);
+    }
+
+    match user_unsettled_score.amount == 0 {
+        true => user_mapper.clear(),
+        false => user_mapper.set(user_unsettled_score),
+    }
+    aggregated_mapper.set(aggregated_unsettled_score);
+}
+
+/// Difference floored at zero, so that a corrupted aggregate cannot block a withdrawal.
+pub fn saturating_sub<M>(minuend: &BigUint<M>, subtrahend: &BigUint<M>) -> BigUint<M>
+where
+    M: ManagedTypeApi,
+{
+    if minuend <= subtrahend {
+        return BigUint::zero();
+    }
+
+    minuend - subtrahend
+}
+
+fn load_unsettled_score<SA>(
+    unsettled_score_mapper: &SingleValueMapper<SA, UnsettledScore<SA>>,
+) -> UnsettledScore<SA>
+where
+    SA: multiversx_sc::api::StorageMapperApi,
+{
+    match unsettled_score_mapper.is_empty() {
+        true => UnsettledScore::new(0),
+        false => unsettled_score_mapper.get(),
+    }
+}
+
+/// Score that was already held when the current epoch started.
+pub fn get_eligible_aggregated_score<C>(
+    sc_ref: &C,
+    staking_module: &StakingModuleType,
+) -> BigUint<C::Api>
+where
+    C: crate::storage::user_data::UserDataStorageModule,
+{
+    let aggregated_score = sc_ref.aggregated_staking_score(staking_module).get();
+    let unsettled_score = load_unsettled_score(&sc_ref.unsettled_aggregated_score(staking_module))
+        .get_amount(sc_ref.blockchain().get_block_epoch());
+    if unsettled_score >= aggregated_score {
         return BigUint::zero();
     }
 
-    let last_claimed_epoch = sc_ref.last_claimed_epoch(staking_module, address).get();
-    let current_epoch = sc_ref.blockchain().get_block_epoch();
+    aggregated_score - unsettled_score
+}
 
+/// Rewards of the per-epoch model earned by `user_score` from `first_epoch` to `last_epoch`.
+pub fn get_legacy_pending_rewards<C>(
+    sc_ref: &C,
+    token_identifier: &EgldOrEsdtTokenIdentifier<C::Api>,
+    staking_module: &StakingModuleType,
+    user_score: &BigUint<C::Api>,
+    first_epoch: u64,
+    last_epoch: u64,
+) -> BigUint<C::Api>
+where
+    C: crate::storage::user_data::UserDataStorageModule,
+{
     let mut pending_reward = BigUint::zero();
-    for current_epoch in last_claimed_epoch + 1..=current_epoch {
-        if sc_ref
-            .reward_rate(current_epoch, staking_module, token_identifier)
-            .is_empty()
-        {
+    if *user_score == 0 {
+        return pending_reward;
+    }
+
+    for current_epoch in first_epoch..=last_epoch {
+        let reward_rate_mapper =
+            sc_ref.reward_rate(current_epoch, staking_module, token_identifier);
+        if reward_rate_mapper.is_empty() {
             continue;
         }
-        let reward_rate = sc_ref
-            .reward_rate(current_epoch, staking_module, token_identifier)
-            .get();
-        pending_reward += &user_score * &reward_rate;
+        pending_reward += user_score * &reward_rate_mapper.get();
     }
 
     pending_reward
 }
+
+pub fn distribute_module_reward<C>(
+    sc_ref: &C,
+    staking_module: &StakingModuleType,
+    payment: EgldOrEsdtTokenPayment<C::Api>,
+    epoch: u64,
+) where
+    C: crate::storage::config::ConfigModule,
+    C: crate::storage::user_data::UserDataStorageModule,
+    C: crate::events::EventsModule,
+{
+    let token_identifier = &payment.token_identifier;
+    let token_nonce = payment.token_nonce;
+    let total_score = get_eligible_aggregated_score(sc_ref, staking_module);
+    let reward_dust_mapper = sc_ref.reward_dust(staking_module, token_identifier, token_nonce);
+    let zero_score_reserve_mapper =
+        sc_ref.zero_score_reserve(staking_module, token_identifier, token_nonce);
+    if total_score == 0 {
+        // nobody to distribute to, the payment waits for the first distribution with stakers
+        zero_score_reserve_mapper.update(|reserve| *reserve += &payment.amount);
+
+        sc_ref.distribute_reward_event(
+            staking_module,
+            epoch,
+            &DistributionEventData {
+                payment,
+                undistributed_rewards: BigUint::zero(),
+                released_reserve: BigUint::zero(),
+                total_score,
+                reward_rate: BigUint::zero(),
+                reward_dust: reward_dust_mapper.get(),
+            },
+        );
+        return;
+    }
+
+    let undistributed_rewards = sc_ref
+        .undistributed_rewards(staking_module, token_identifier, token_nonce)
+        .take();
+    let released_reserve = zero_score_reserve_mapper.take();
+    let distributable_amount =
+        &payment.amount + &undistributed_rewards + &released_reserve + reward_dust_mapper.get();
+    let reward_rate = &distributable_amount / &total_score;
+
+    let precision = sc_ref
+        .get_reward_token(token_identifier, token_nonce)
+        .precision;
+    let scaled_reward_rate = &distributable_amount * &precision / &total_score;
+    let reward_per_share_mapper =
+        sc_ref.reward_per_share(staking_module, token_identifier, token_nonce);
+    let last_distribution_epoch_mapper =
+        sc_ref.last_distribution_epoch(staking_module, token_identifier, token_nonce);
+    let last_distribution_epoch = last_distribution_epoch_mapper.get();
+    if last_distribution_epoch < epoch {
+        sc_ref
+            .epoch_end_reward_per_share(
+                last_distribution_epoch,
+                staking_module,
+                token_identifier,
+                token_nonce,
+            )
+            .set(reward_per_share_mapper.get());
+        last_distribution_epoch_mapper.set(epoch);
+    }
+    reward_per_share_mapper.update(|reward_per_share| *reward_per_share += &scaled_reward_rate);
+
+    let distributed_amount = scaled_reward_rate * &total_score / precision;
+    sc_ref.add_distributed_rewards(token_identifier, token_nonce, &distributed_amount);
+    let reward_dust = distributable_amount - distributed_amount;
+    reward_dust_mapper.set(&reward_dust);
+
+    // the per-epoch rates up to the upgrade are only walked for positions never checkpointed,
+    // which settle later distributions of the upgrade epoch through the accumulator as well.
+    // Rates are keyed by token identifier as in the per-epoch model, SFT rewards are left out
+    if token_nonce == 0 && !is_legacy_reward_epoch(sc_ref, epoch) {
+        sc_ref
+            .reward_rate(epoch, staking_module, token_identifier)
+            .update(|epoch_reward_rate| *epoch_reward_rate += &reward_rate);
+    }
+
+    sc_ref.distribute_reward_event(
+        staking_module,
+        epoch,
+        &DistributionEventData {
+            payment,
+            undistributed_rewards,
+            released_reserve,
+            total_score,
+            reward_rate,
+            reward_dust,
+        },
+    );
+}
+
+pub fn release_scheduled_rewards<C>(
+    sc_ref: &C,
+    staking_module: &StakingModuleType,
+    reward_token: &RewardToken<C::Api>,
+) where
+    C: crate::storage::config::ConfigModule,
+    C: crate::storage::user_data::UserDataStorageModule,
+    C: crate::events::EventsModule,
+    C: crate::storage::pause::PauseStorageModule,
+{
+    let token_identifier = &reward_token.token_identifier;
+    let reward_schedule_mapper =
+        sc_ref.reward_schedule(staking_module, token_identifier, reward_token.token_nonce);
+    // epochs elapsed in emergency mode are released together once it is disabled
+    if reward_schedule_mapper.is_empty()
+        || !reward_token.is_active()
+        || sc_ref.emergency_mode().get()
+    {
+        return;
+    }
+
+    let block_epoch = sc_ref.blockchain().get_block_epoch();
+    let mut reward_schedule = reward_schedule_mapper.get();
+    let released_amount = reward_schedule.release(block_epoch);
+    if released_amount == 0 {
+        return;
+    }
+
+    if reward_schedule.remaining_amount == 0 {
+        reward_schedule_mapper.clear();
+    } else {
+        reward_schedule_mapper.set(reward_schedule);
+    }
+
+    distribute_module_reward(
+        sc_ref,
+        staking_module,
+        EgldOrEsdtTokenPayment::new(
+            token_identifier.clone(),
+            reward_token.token_nonce,
+            released_amount,
+        ),
+        block_epoch,
+    );
+}
+
+pub fn is_legacy_reward_epoch<C>(sc_ref: &C, epoch: u64) -> bool
+where
+    C: crate::storage::user_data::UserDataStorageModule,
+{
+    let legacy_reward_epoch_end = sc_ref.legacy_reward_epoch_end();
+
+    !legacy_reward_epoch_end.is_empty() && epoch <= legacy_reward_epoch_end.get()
+}
+
+pub fn migrate_legacy_unbonding_assets<C>(sc_ref: &C, address: &ManagedAddress<C::Api>)
+where
+    C: crate::storage::config::ConfigModule,
+    C: crate::storage::user_data::UserDataStorageModule,
+{
+    let mut legacy_unbonding_assets = sc_ref.unbonding_assets(address);
+    if legacy_unbonding_assets.is_empty() {
+        return;
+    }
+
+    let unbonding_time_penalty = sc_ref.unbonding_time_penalty().get();
+    for (start_unbonding_timestamp, unbonding_payload) in legacy_unbonding_assets.iter() {
+        let batch_id = sc_ref.get_next_unbonding_batch_id(address);
+        sc_ref.unbonding_batches(address).insert(
+            batch_id,
+            UnbondingBatch::new(
+                unbonding_payload,
+                start_unbonding_timestamp,
+                unbonding_time_penalty,
+            ),
+        );
+    }
+
+    legacy_unbonding_assets.clear();
+}
+
+/// Moves the tokens of the legacy reward token sets into the registry. Besides its mapped
+/// modules, a token keeps targeting every module it was already distributed to through the
+/// accumulator, along with the `legacy_reward_targets` the owner lists for the per-epoch
+/// `reward_rate` of the legacy model.
+pub fn migrate_legacy_reward_tokens<C>(
+    sc_ref: &C,
+    legacy_reward_targets: MultiValueEncoded<
+        C::Api,
+        MultiValue2<EgldOrEsdtTokenIdentifier<C::Api>, StakingModuleType>,
+    >,
+) where
+    C: crate::storage::config::ConfigModule,
+    C: crate::storage::user_data::UserDataStorageModule,
+{
+    // listed targets are merged into the registry, so repeating them on a later upgrade is a no-op
+    let is_upgrade = !sc_ref.legacy_reward_epoch_end().is_empty();
+    for legacy_reward_target in legacy_reward_targets.into_iter() {
+        let (token_identifier, staking_module) = legacy_reward_target.into_tuple();
+        if is_upgrade {
+            sc_ref
+                .legacy_reward_tokens()
+                .insert(token_identifier.clone());
+        }
+        let reward_token_key = (token_identifier.clone(), 0);
+        let mut reward_token = match sc_ref.reward_registry().get(&reward_token_key) {
+            Some(reward_token) => reward_token,
+            None => RewardToken::new(
+                token_identifier.clone(),
+                0,
+                REWARD_PER_SHARE_PRECISION,
+                sc_ref.reward_token_generation(&token_identifier, 0).get(),
+            ),
+        };
+        if !reward_token.targets(&staking_module) {
+            reward_token.staking_modules.push(staking_module);
+            sc_ref
+                .reward_registry()
+                .insert(reward_token_key, reward_token);
+        }
+    }
+
+    let mut legacy_reward_token_identifiers = sc_ref.reward_token_identifiers();
+    let mut legacy_reward_token_id_mapping = sc_ref.reward_token_id_mapping();
+    if legacy_reward_token_identifiers.is_empty() && legacy_reward_token_id_mapping.is_empty() {
+        return;
+    }
+
+    for (token_identifier, _) in legacy_reward_token_id_mapping.iter() {
+        legacy_reward_token_identifiers.insert(token_identifier);
+    }
+    for token_identifier in legacy_reward_token_identifiers.iter() {
+        if is_upgrade {
+            sc_ref
+                .legacy_reward_tokens()
+                .insert(token_identifier.clone());
+        }
+        let reward_token_key = (token_identifier.clone(), 0);
+        let mut reward_token = match sc_ref.reward_registry().get(&reward_token_key) {
+            Some(reward_token) => reward_token,
+            None => RewardToken::new(token_identifier.clone(), 0, REWARD_PER_SHARE_PRECISION, 0),
+        };
+        for staking_module in StakingModuleType::iter() {
+            if !reward_token.targets(staking_module)
+                && (legacy_reward_token_id_mapping
+                    .contains(&(token_identifier.clone(), staking_module.clone()))
+                    || !sc_ref
+                        .reward_per_share(staking_module, &token_identifier, 0)
+                        .is_empty())
+            {
+                reward_token.staking_modules.push(staking_module.clone());
+            }
+        }
+        sc_ref
+            .reward_registry()
+            .insert(reward_token_key, reward_token);
+    }
+
+    legacy_reward_token_identifiers.clear();
+    legacy_reward_token_id_mapping.clear();
+}
+
+/// Sends ESDT rewards in one multi-transfer and any EGLD reward alongside it.
+pub fn send_reward_payments<C>(
+    sc_ref: &C,
+    receiver: &ManagedAddress<C::Api>,
+    payments: &ManagedVec<C::Api, RewardPayment<C::Api>>,
+) where
+    C: ContractBase,
+{
+    let mut esdt_payments = ManagedVec::<C::Api, EsdtTokenPayment<C::Api>>::new();
+    let mut egld_amount = BigUint::zero();
+    for payment in payments.iter() {
+        match payment.token_identifier.into_esdt_option() {
+            Some(token_identifier) => esdt_payments.push(EsdtTokenPayment::new(
+                token_identifier,
+                payment.token_nonce,
+                payment.amount,
+            )),
+            None => egld_amount += payment.amount,
+        }
+    }
+
+    if !esdt_payments.is_empty() {
+        sc_ref.send().direct_multi(receiver, &esdt_payments);
+    }
+    if egld_amount > 0 {
+        sc_ref.send().direct_egld(receiver, &egld_amount);
+    }
+}
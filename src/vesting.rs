@@ -0,0 +1,212 @@
+use crate::{
+    constants::{
+        ERR_EARLY_WITHDRAW_DISABLED, ERR_INVALID_VESTING_CONFIG, ERR_NOTHING_TO_CLAIM,
+        MAX_PERCENTAGE,
+    },
+    staking_modules::staking_module_type::StakingModuleType,
+    types::{
+        reward_payment::RewardPayment,
+        vesting::{VestingConfig, VestingSchedule},
+    },
+    utils::send_reward_payments,
+};
+
+multiversx_sc::imports!();
+
+#[multiversx_sc::module]
+pub trait VestingModule:
+    crate::storage::config::ConfigModule
+    + crate::storage::user_data::UserDataStorageModule
+    + crate::storage::pause::PauseStorageModule
+    + crate::pause::PauseModule
+    + crate::events::EventsModule
+{
+    #[only_owner]
+    #[endpoint(setVestingConfig)]
+    fn set_vesting_config(
+        &self,
+        reward_token_identifier: EgldOrEsdtTokenIdentifier,
+        vesting_epochs: u64,
+        early_withdraw_penalty: u64,
+        penalty_staking_module: StakingModuleType,
+        opt_reward_token_nonce: OptionalValue<u64>,
+    ) {
+        let reward_token_nonce = opt_reward_token_nonce.into_option().unwrap_or_default();
+        let reward_token = self.get_reward_token(&reward_token_identifier, reward_token_nonce);
+        require!(
+            vesting_epochs > 0
+                && early_withdraw_penalty <= MAX_PERCENTAGE
+                && reward_token.targets(&penalty_staking_module),
+            ERR_INVALID_VESTING_CONFIG
+        );
+
+        let vesting_config = VestingConfig {
+            vesting_epochs,
+            early_withdraw_penalty,
+            penalty_staking_module,
+        };
+        self.vesting_config(&reward_token_identifier, reward_token_nonce)
+            .set(&vesting_config);
+        self.vesting_config_event(
+            &reward_token_identifier,
+            reward_token_nonce,
+            &vesting_config,
+        );
+    }
+
+    #[only_owner]
+    #[endpoint(removeVestingConfig)]
+    fn remove_vesting_config(
+        &self,
+        reward_token_identifier: EgldOrEsdtTokenIdentifier,
+        opt_reward_token_nonce: OptionalValue<u64>,
+    ) {
+        let reward_token_nonce = opt_reward_token_nonce.into_option().unwrap_or_default();
+        self.vesting_config(&reward_token_identifier, reward_token_nonce)
+            .clear();
+        self.remove_vesting_config_event(&reward_token_identifier, reward_token_nonce);
+    }
+
+    /// Pays the unlocked amounts to the caller's reward receiver, whichever address the rewards
+    /// were claimed to.
+    #[endpoint(withdrawVested)]
+    fn withdraw_vested(
+        &self,
+        reward_tokens: MultiValueEncoded<MultiValue2<EgldOrEsdtTokenIdentifier, u64>>,
+    ) {
+        self.require_claim_rewards_not_paused();
+        let caller = self.blockchain().get_caller();
+        let block_epoch = self.blockchain().get_block_epoch();
+
+        // no tokens withdraw the schedules of every registered token, swept ones have to be named
+        let reward_tokens = match reward_tokens.is_empty() {
+            true => self
+                .reward_registry()
+                .keys()
+                .map(MultiValue2::from)
+                .collect(),
+            false => reward_tokens,
+        };
+        let mut payments = ManagedVec::new();
+        for reward_token in reward_tokens.into_iter() {
+            let (reward_token_identifier, reward_token_nonce) = reward_token.into_tuple();
+            let vesting_schedule_mapper =
+                self.vesting_schedule(&caller, &reward_token_identifier, reward_token_nonce);
+            if vesting_schedule_mapper.is_empty() {
+                continue;
+            }
+
+            let mut vesting_schedule = vesting_schedule_mapper.get();
+            let amount = vesting_schedule.take_unlocked(block_epoch);
+            match vesting_schedule.is_empty() {
+                true => vesting_schedule_mapper.clear(),
+                false => vesting_schedule_mapper.set(vesting_schedule),
+            }
+            if amount > 0 {
+                payments.push(RewardPayment::new(
+                    reward_token_identifier,
+                    reward_token_nonce,
+                    amount,
+                ));
+            }
+        }
+        require!(!payments.is_empty(), ERR_NOTHING_TO_CLAIM);
+
+        let receiver = self.get_reward_receiver(&caller);
+        send_reward_payments(self, &receiver, &payments);
+        self.withdraw_vested_event(&caller, &receiver, &payments);
+    }
+
+    #[endpoint(withdrawVestedEarly)]
+    fn withdraw_vested_early(
+        &self,
+        reward_token_identifier: EgldOrEsdtTokenIdentifier,
+        opt_reward_token_nonce: OptionalValue<u64>,
+    ) {
+        self.require_claim_rewards_not_paused();
+        let reward_token_nonce = opt_reward_token_nonce.into_option().unwrap_or_default();
+        let vesting_config_mapper =
+            self.vesting_config(&reward_token_identifier, reward_token_nonce);
+        require!(
+            !vesting_config_mapper.is_empty(),
+            ERR_EARLY_WITHDRAW_DISABLED
+        );
+        let vesting_config = vesting_config_mapper.get();
+        require!(
+            vesting_config.early_withdraw_penalty > 0,
+            ERR_EARLY_WITHDRAW_DISABLED
+        );
+
+        let caller = self.blockchain().get_caller();
+        let vesting_schedule_mapper =
+            self.vesting_schedule(&caller, &reward_token_identifier, reward_token_nonce);
+        require!(!vesting_schedule_mapper.is_empty(), ERR_NOTHING_TO_CLAIM);
+
+        let mut vesting_schedule = vesting_schedule_mapper.take();
+        vesting_schedule.update(self.blockchain().get_block_epoch());
+        let penalty = &vesting_schedule.locked_amount * vesting_config.early_withdraw_penalty
+            / MAX_PERCENTAGE;
+        if penalty > 0 {
+            self.undistributed_rewards(
+                &vesting_config.penalty_staking_module,
+                &reward_token_identifier,
+                reward_token_nonce,
+            )
+            .update(|undistributed_rewards| *undistributed_rewards += &penalty);
+        }
+
+        let amount = vesting_schedule.unlocked_amount + vesting_schedule.locked_amount - &penalty;
+        require!(amount > 0, ERR_NOTHING_TO_CLAIM);
+
+        let receiver = self.get_reward_receiver(&caller);
+        let payment =
+            EgldOrEsdtTokenPayment::new(reward_token_identifier, reward_token_nonce, amount);
+        self.send().direct(
+            &receiver,
+            &payment.token_identifier,
+            payment.token_nonce,
+            &payment.amount,
+        );
+        self.withdraw_vested_early_event(&caller, &receiver, &penalty, &payment);
+    }
+
+    /// Locks the rewards of tokens with a vesting configuration into the user's vesting schedules.
+    /// Returns the rewards that can be paid right away.
+    fn vest_rewards(
+        &self,
+        user: &ManagedAddress,
+        rewards: &ManagedVec<RewardPayment<Self::Api>>,
+    ) -> ManagedVec<RewardPayment<Self::Api>> {
+        let block_epoch = self.blockchain().get_block_epoch();
+        let mut paid_rewards = ManagedVec::new();
+        let mut vested_rewards = ManagedVec::new();
+        for reward in rewards.iter() {
+            let vesting_config_mapper =
+                self.vesting_config(&reward.token_identifier, reward.token_nonce);
+            if vesting_config_mapper.is_empty() {
+                paid_rewards.push(reward);
+                continue;
+            }
+
+            let vesting_schedule_mapper =
+                self.vesting_schedule(user, &reward.token_identifier, reward.token_nonce);
+            let mut vesting_schedule = match vesting_schedule_mapper.is_empty() {
+                true => VestingSchedule::new(block_epoch),
+                false => vesting_schedule_mapper.get(),
+            };
+            vesting_schedule.lock(
+                &reward.amount,
+                block_epoch,
+                vesting_config_mapper.get().vesting_epochs,
+            );
+            vesting_schedule_mapper.set(vesting_schedule);
+            vested_rewards.push(reward);
+        }
+
+        if !vested_rewards.is_empty() {
+            self.vest_rewards_event(user, &vested_rewards);
+        }
+
+        paid_rewards
+    }
+}
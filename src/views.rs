@@ -16,6 +16,8 @@ pub trait ViewsModule:
     + crate::storage::user_data::UserDataStorageModule
     + crate::storage::score::ScoreStorageModule
     + crate::requirements::RequirementsModule
+    + crate::events::EventsModule
+    + crate::storage::pause::PauseStorageModule
 {
     #[view(getGeneralStakingData)]
     fn get_general_staking_data(&self) -> ManagedVec<UIAggregatedPoolScore<Self::Api>> {
@@ -118,15 +120,51 @@ pub trait ViewsModule:
         context.get_base_user_score(&staking_module_type)
     }
 
+    #[view(getRewardScheduleRemainingEpochs)]
+    fn get_reward_schedule_remaining_epochs(
+        &self,
+        staking_module_type: StakingModuleType,
+        reward_token_identifier: EgldOrEsdtTokenIdentifier,
+        opt_reward_token_nonce: OptionalValue<u64>,
+    ) -> u64 {
+        let reward_token_nonce = opt_reward_token_nonce.into_option().unwrap_or_default();
+        let reward_schedule_mapper = self.reward_schedule(
+            &staking_module_type,
+            &reward_token_identifier,
+            reward_token_nonce,
+        );
+        if reward_schedule_mapper.is_empty() {
+            return 0;
+        }
+
+        reward_schedule_mapper
+            .get()
+            .get_remaining_epochs(self.blockchain().get_block_epoch())
+    }
+
     fn parse_unbonding_assets(
         &self,
         address: &ManagedAddress,
     ) -> ManagedVec<UIUnbondingAsset<Self::Api>> {
         let mut unbonding_assets = ManagedVec::new();
-        for key in self.unbonding_assets(address).keys() {
-            let unbonding_payload = self.unbonding_assets(address).get(&key).unwrap();
+        for (batch_id, unbonding_batch) in self.unbonding_batches(address).iter() {
+            unbonding_assets.push(UIUnbondingAsset {
+                batch_id,
+                start_timestamp: unbonding_batch.start_timestamp,
+                release_timestamp: unbonding_batch.release_timestamp,
+                assets: unbonding_batch.payload,
+            })
+        }
+
+        // legacy batches receive the next batch ids once they get migrated
+        let unbonding_time_penalty = self.unbonding_time_penalty().get();
+        let mut batch_id = self.last_unbonding_batch_id(address).get();
+        for (start_timestamp, unbonding_payload) in self.unbonding_assets(address).iter() {
+            batch_id += 1;
             unbonding_assets.push(UIUnbondingAsset {
-                timestamp: key,
+                batch_id,
+                start_timestamp,
+                release_timestamp: start_timestamp + unbonding_time_penalty,
                 assets: unbonding_payload,
             })
         }
@@ -1,5 +1,15 @@
+use multiversx_sc::types::{EgldOrEsdtTokenIdentifier, MultiValueEncoded};
+use multiversx_sc_scenario::{
+    managed_address, managed_biguint, managed_token_id_wrapped, rust_biguint,
+};
 use nft_staking::{
-    constants::ERR_NOTHING_TO_CLAIM, staking_modules::staking_module_type::StakingModuleType,
+    constants::{
+        ERR_LEGACY_REWARDS_NOT_SETTLED, ERR_NOTHING_TO_CLAIM, MAX_LEGACY_SETTLEMENT_EPOCHS,
+    },
+    owner::OwnerModule,
+    staking_modules::staking_module_type::StakingModuleType,
+    storage::user_data::UserDataStorageModule,
+    NftStakingContract,
 };
 
 use crate::setup::{
@@ -9,6 +19,7 @@ use crate::setup::{
 };
 
 #[test]
+#[allow(deprecated)]
 fn successful_claim_rewards() {
     let aggregated_score = 100_000;
     let reward = 100_000;
@@ -20,6 +31,7 @@ fn successful_claim_rewards() {
     setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
     setup.stake(&transfers, NO_ERR_MSG);
 
+    setup.b_mock.set_block_epoch(2);
     setup.set_aggregated_score(StakingModuleType::All, aggregated_score);
     setup.distribute_reward(reward, NO_ERR_MSG);
 
@@ -32,6 +44,7 @@ fn successful_claim_rewards() {
 }
 
 #[test]
+#[allow(deprecated)]
 fn double_claim_rewards_fail() {
     let mut setup = ContractSetup::new(nft_staking::contract_obj);
     let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
@@ -39,6 +52,7 @@ fn double_claim_rewards_fail() {
     setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
     setup.stake(&transfers, NO_ERR_MSG);
 
+    setup.b_mock.set_block_epoch(2);
     setup.distribute_reward(100_000, NO_ERR_MSG);
     setup.claim_rewards(NO_ERR_MSG);
     setup.claim_rewards(ERR_NOTHING_TO_CLAIM);
@@ -75,14 +89,16 @@ fn not_distributed_epoch_not_claimed() {
     setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
     setup.stake(&transfers, NO_ERR_MSG);
 
-    setup.distribute_reward(100_000, NO_ERR_MSG);
     setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.b_mock.set_block_epoch(3);
     setup.claim_rewards(NO_ERR_MSG);
     setup.distribute_reward(100_000, NO_ERR_MSG);
     setup.assert_pending_reward(100_000);
 }
 
 #[test]
+#[allow(deprecated)]
 fn mixed_rewards_claim_successful() {
     let mut setup = ContractSetup::new(nft_staking::contract_obj);
     let transfer = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
@@ -97,6 +113,7 @@ fn mixed_rewards_claim_successful() {
 
     setup.stake(&transfer, NO_ERR_MSG);
 
+    setup.b_mock.set_block_epoch(2);
     setup.distribute_reward(100_000, NO_ERR_MSG);
     setup.distribute_secondary_reward(
         SECONDARY_REWARD_TOKEN_ID_1,
@@ -112,6 +129,7 @@ fn mixed_rewards_claim_successful() {
 }
 
 #[test]
+#[allow(deprecated)]
 fn mixed_partial_rewards_claim_successful() {
     let reward_amount = 100_000;
     let aggregated_score = 10;
@@ -139,6 +157,7 @@ fn mixed_partial_rewards_claim_successful() {
 
     setup.stake(&transfer, NO_ERR_MSG);
 
+    setup.b_mock.set_block_epoch(2);
     setup.set_aggregated_score(StakingModuleType::All, aggregated_score);
     setup.set_aggregated_score(StakingModuleType::XBunnies, aggregated_score);
     setup.distribute_reward(reward_amount, NO_ERR_MSG);
@@ -163,3 +182,379 @@ fn mixed_partial_rewards_claim_successful() {
         expected_secondary_token_amount,
     );
 }
+
+#[test]
+#[allow(deprecated)]
+fn claim_rewards_for_selected_tokens_only() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfer = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_token_score(StakingModuleType::XBunnies, POOL1_TOKEN_ID, 1);
+    setup.register_token_as_eligible_reward_for_pool(
+        SECONDARY_REWARD_TOKEN_ID_1,
+        StakingModuleType::XBunnies,
+    );
+    setup.stake(&transfer, NO_ERR_MSG);
+
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.distribute_secondary_reward(
+        SECONDARY_REWARD_TOKEN_ID_1,
+        POOL1_TOKEN_ID,
+        100_000,
+        NO_ERR_MSG,
+    );
+
+    setup.claim_rewards_for_tokens(&[SECONDARY_REWARD_TOKEN_ID_1], NO_ERR_MSG);
+    setup.assert_user_token_balance(SECONDARY_REWARD_TOKEN_ID_1, 0, 100_000);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 0);
+    setup.assert_pending_reward(100_000);
+    setup.claim_rewards_for_tokens(&[SECONDARY_REWARD_TOKEN_ID_1], ERR_NOTHING_TO_CLAIM);
+
+    setup.claim_rewards_for_tokens(&[REWARD_TOKEN_ID], NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 100_000);
+    setup.assert_pending_reward(0);
+}
+
+#[test]
+#[allow(deprecated)]
+fn claim_rewards_to_receiver() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let receiver = setup.b_mock.create_user_account(&rust_biguint!(0));
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.claim_rewards_to(&receiver, NO_ERR_MSG);
+    setup
+        .b_mock
+        .check_esdt_balance(&receiver, REWARD_TOKEN_ID, &rust_biguint!(100_000));
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 0);
+    setup.claim_rewards_to(&receiver, ERR_NOTHING_TO_CLAIM);
+}
+
+#[test]
+#[allow(deprecated)]
+fn claim_rewards_honors_reward_receiver() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let receiver = setup.b_mock.create_user_account(&rust_biguint!(0));
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+
+    setup.set_reward_receiver(&receiver);
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.claim_rewards(NO_ERR_MSG);
+    setup
+        .b_mock
+        .check_esdt_balance(&receiver, REWARD_TOKEN_ID, &rust_biguint!(100_000));
+
+    setup.clear_reward_receiver();
+    setup.b_mock.set_block_epoch(3);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 100_000);
+}
+
+#[test]
+#[allow(deprecated)]
+fn egld_and_esdt_rewards_claimed_together() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup
+        .b_mock
+        .execute_tx(
+            &setup.owner_address,
+            &setup.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.register_reward_token(
+                    EgldOrEsdtTokenIdentifier::egld(),
+                    StakingModuleType::All,
+                    None.into(),
+                );
+            },
+        )
+        .assert_ok();
+    let owner_address = setup.owner_address.clone();
+    setup
+        .b_mock
+        .set_egld_balance(&owner_address, &rust_biguint!(50_000));
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.distribute_egld_reward(50_000, NO_ERR_MSG);
+
+    let user_address = setup.user_address.clone();
+    setup
+        .b_mock
+        .execute_query(&setup.contract_wrapper, |sc| {
+            let pending_rewards = sc.get_pending_reward(managed_address!(&user_address));
+            assert_eq!(pending_rewards.len(), 2);
+            for pending_reward in pending_rewards.iter() {
+                let expected_amount = match pending_reward.token_identifier.is_egld() {
+                    true => 50_000u64,
+                    false => 100_000u64,
+                };
+                assert_eq!(pending_reward.amount, expected_amount);
+            }
+        })
+        .assert_ok();
+
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 100_000);
+    setup
+        .b_mock
+        .check_egld_balance(&user_address, &rust_biguint!(50_000));
+}
+
+#[test]
+#[allow(deprecated)]
+fn late_staker_does_not_earn_past_rewards() {
+    let reward = 100_000u64;
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_aggregated_score(StakingModuleType::All, 9);
+    setup.distribute_reward(reward, NO_ERR_MSG);
+
+    setup.b_mock.set_block_epoch(2);
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.stake(&transfers, NO_ERR_MSG);
+    setup.assert_pending_reward(0);
+
+    setup.b_mock.set_block_epoch(3);
+    setup.distribute_reward(reward, NO_ERR_MSG);
+    setup.assert_pending_reward(reward / 10);
+}
+
+#[test]
+#[allow(deprecated)]
+fn legacy_epoch_rewards_settled_after_upgrade() {
+    let legacy_reward_rate = 1_000u64;
+    let reward = 100_000u64;
+
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    // simulate a position opened under the per-epoch model and last claimed in epoch 2
+    let user_address = setup.user_address.clone();
+    setup
+        .b_mock
+        .execute_tx(
+            &setup.owner_address,
+            &setup.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let user = managed_address!(&user_address);
+                let token_id = managed_token_id_wrapped!(REWARD_TOKEN_ID);
+                sc.user_reward_per_share(&StakingModuleType::All, &token_id, 0, &user)
+                    .clear();
+                sc.last_claimed_epoch(&StakingModuleType::All, &user).set(2);
+                for epoch in 1..=5 {
+                    sc.reward_rate(epoch, &StakingModuleType::All, &token_id)
+                        .set(managed_biguint!(legacy_reward_rate));
+                }
+                sc.legacy_reward_epoch_end().set(5);
+            },
+        )
+        .assert_ok();
+
+    setup.b_mock.set_esdt_balance(
+        setup.contract_wrapper.address_ref(),
+        REWARD_TOKEN_ID,
+        &rust_biguint!(3 * legacy_reward_rate),
+    );
+
+    setup.b_mock.set_block_epoch(6);
+    setup.assert_pending_reward(3 * legacy_reward_rate);
+
+    setup.distribute_reward(reward, NO_ERR_MSG);
+    setup.assert_pending_reward(3 * legacy_reward_rate + reward);
+
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_pending_reward(0);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 3 * legacy_reward_rate + reward);
+}
+
+#[test]
+#[allow(deprecated)]
+fn legacy_rewards_settled_once_before_first_distribution() {
+    let legacy_reward_rate = 1_000u64;
+
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&transfers, NO_ERR_MSG);
+    setup.set_legacy_reward_position(2, 5, legacy_reward_rate);
+    setup.b_mock.set_esdt_balance(
+        setup.contract_wrapper.address_ref(),
+        REWARD_TOKEN_ID,
+        &rust_biguint!(9 * legacy_reward_rate),
+    );
+
+    // the accumulators are still zero, the checkpoint alone must not send the position back
+    setup.b_mock.set_block_epoch(6);
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 3 * legacy_reward_rate);
+    setup.assert_pending_reward(0);
+    setup.claim_rewards(ERR_NOTHING_TO_CLAIM);
+
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 2, 1)], NO_ERR_MSG);
+    setup.claim_rewards(ERR_NOTHING_TO_CLAIM);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 3 * legacy_reward_rate);
+}
+
+#[test]
+#[allow(deprecated)]
+fn staker_joining_after_upgrade_earns_no_legacy_rewards() {
+    let legacy_reward_rate = 1_000u64;
+    let reward = 100_000u64;
+
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_legacy_reward_position(0, 5, legacy_reward_rate);
+    setup.b_mock.set_esdt_balance(
+        setup.contract_wrapper.address_ref(),
+        REWARD_TOKEN_ID,
+        &rust_biguint!(5 * legacy_reward_rate),
+    );
+
+    setup.b_mock.set_block_epoch(6);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+    setup.assert_pending_reward(0);
+    setup.claim_rewards(ERR_NOTHING_TO_CLAIM);
+
+    setup.b_mock.set_block_epoch(7);
+    setup.distribute_reward(reward, NO_ERR_MSG);
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, reward);
+}
+
+#[test]
+#[allow(deprecated)]
+fn legacy_rewards_settled_over_several_calls() {
+    let legacy_reward_rate = 10u64;
+    let legacy_reward_epoch_end = MAX_LEGACY_SETTLEMENT_EPOCHS + 20;
+
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&transfers, NO_ERR_MSG);
+    setup.set_legacy_reward_position(0, legacy_reward_epoch_end, legacy_reward_rate);
+    setup.b_mock.set_esdt_balance(
+        setup.contract_wrapper.address_ref(),
+        REWARD_TOKEN_ID,
+        &rust_biguint!(legacy_reward_epoch_end * legacy_reward_rate),
+    );
+
+    setup.b_mock.set_block_epoch(legacy_reward_epoch_end + 1);
+    setup.assert_pending_reward(legacy_reward_epoch_end * legacy_reward_rate);
+    setup.claim_rewards(ERR_LEGACY_REWARDS_NOT_SETTLED);
+
+    // each call walks a bounded range of epochs and stores its progress
+    setup.settle_legacy_rewards(50, NO_ERR_MSG);
+    setup.assert_stored_rewards(50 * legacy_reward_rate);
+    setup.assert_pending_reward(legacy_reward_epoch_end * legacy_reward_rate);
+    let user_address = setup.user_address.clone();
+    setup.migrate_reward_checkpoints(50, &[user_address]);
+    setup.assert_stored_rewards(100 * legacy_reward_rate);
+
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(
+        REWARD_TOKEN_ID,
+        0,
+        legacy_reward_epoch_end * legacy_reward_rate,
+    );
+    setup.claim_rewards(ERR_NOTHING_TO_CLAIM);
+}
+
+#[test]
+#[allow(deprecated)]
+fn pending_rewards_stored_before_nonce_keys_claimed_once() {
+    let legacy_pending_rewards = 5_000u64;
+
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    // rewards secured by an earlier version of the contract, keyed by token identifier only
+    let user_address = setup.user_address.clone();
+    setup
+        .b_mock
+        .execute_tx(
+            &setup.owner_address,
+            &setup.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.legacy_pending_rewards(
+                    &managed_address!(&user_address),
+                    &managed_token_id_wrapped!(REWARD_TOKEN_ID),
+                )
+                .set(managed_biguint!(legacy_pending_rewards));
+            },
+        )
+        .assert_ok();
+    setup.b_mock.set_esdt_balance(
+        setup.contract_wrapper.address_ref(),
+        REWARD_TOKEN_ID,
+        &rust_biguint!(legacy_pending_rewards),
+    );
+    setup.assert_pending_reward(legacy_pending_rewards);
+
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, legacy_pending_rewards);
+    setup.claim_rewards(ERR_NOTHING_TO_CLAIM);
+}
+
+#[test]
+#[allow(deprecated)]
+fn rewards_distributed_in_upgrade_epoch_settled_through_accumulator() {
+    let reward = 100_000u64;
+
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    // upgrading marks the current epoch as the last one of the per-epoch model
+    setup.b_mock.set_block_epoch(5);
+    setup
+        .b_mock
+        .execute_tx(
+            &setup.owner_address,
+            &setup.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.init(
+                    managed_token_id_wrapped!(REWARD_TOKEN_ID),
+                    MultiValueEncoded::new(),
+                );
+                assert_eq!(sc.legacy_reward_epoch_end().get(), 5);
+            },
+        )
+        .assert_ok();
+
+    setup.distribute_reward(reward, NO_ERR_MSG);
+    setup.assert_reward_rate(StakingModuleType::All, REWARD_TOKEN_ID, 5, 0);
+    setup.assert_pending_reward(reward);
+
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, reward);
+}
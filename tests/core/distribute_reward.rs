@@ -1,17 +1,25 @@
+use multiversx_sc_scenario::{managed_token_id_wrapped, rust_biguint};
 use nft_staking::{
-    constants::ERR_REWARD_ALREADY_DISTRIBUTED,
+    constants::{
+        ERR_INVALID_DISTRIBUTION_WEIGHTS, ERR_INVALID_REWARD_SCHEDULE, ERR_INVALID_REWARD_TARGET,
+        ERR_INVALID_REWARD_TOKEN_ID, ERR_NOTHING_TO_CLAIM, ERR_NOTHING_TO_DISTRIBUTE,
+        ERR_REWARD_ALREADY_DISTRIBUTED,
+    },
     staking_modules::staking_module_type::StakingModuleType,
+    storage::user_data::UserDataStorageModule,
 };
 
 use crate::setup::{
     constants::{
         NO_ERR_MSG, POOL1_TOKEN_ID, POOL2_TOKEN_ID, REWARD_TOKEN_ID, SECONDARY_REWARD_TOKEN_ID_1,
+        SFT_REWARD_TOKEN_ID, SFT_REWARD_TOKEN_NONCE,
     },
     types::new_nft_transfer,
     ContractSetup,
 };
 
 #[test]
+#[allow(deprecated)]
 fn simple_successful_primary_distribution() {
     let mut setup = ContractSetup::new(nft_staking::contract_obj);
     let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
@@ -19,20 +27,68 @@ fn simple_successful_primary_distribution() {
     setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
     setup.stake(&transfers, NO_ERR_MSG);
 
+    setup.b_mock.set_block_epoch(2);
     setup.distribute_reward(100_000, NO_ERR_MSG);
     setup.assert_pending_reward(100_000);
 }
 
 #[test]
-fn double_primary_distribution_fails() {
+#[allow(deprecated)]
+fn double_primary_distribution_accumulates() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.assert_reward_rate(StakingModuleType::All, REWARD_TOKEN_ID, 2, 200_000);
+    setup.assert_pending_reward(200_000);
+}
+
+#[test]
+#[allow(deprecated)]
+fn distribution_timestamps_recorded_per_epoch() {
     let mut setup = ContractSetup::new(nft_staking::contract_obj);
     let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
     setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
     setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
     setup.stake(&transfers, NO_ERR_MSG);
 
+    setup.b_mock.set_block_epoch(2);
+    setup.b_mock.set_block_timestamp(100);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.b_mock.set_block_timestamp(200);
     setup.distribute_reward(100_000, NO_ERR_MSG);
-    setup.distribute_reward(100_000, ERR_REWARD_ALREADY_DISTRIBUTED);
+
+    setup
+        .b_mock
+        .execute_query(&setup.contract_wrapper, |sc| {
+            let reward_token_id = managed_token_id_wrapped!(REWARD_TOKEN_ID);
+            assert_eq!(
+                sc.reward_distribution_timestamp(2, &reward_token_id).get(),
+                200
+            );
+            let timestamps = sc.reward_distribution_timestamps(2, &reward_token_id);
+            assert_eq!(timestamps.len(), 2);
+            assert_eq!(timestamps.get(1), 100);
+            assert_eq!(timestamps.get(2), 200);
+        })
+        .assert_ok();
+}
+
+#[test]
+fn same_epoch_distributions_use_score_at_deposit_time() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+
+    setup.set_aggregated_score(StakingModuleType::All, 100);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.set_aggregated_score(StakingModuleType::All, 200);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+
+    setup.assert_reward_rate(StakingModuleType::All, REWARD_TOKEN_ID, 1, 1_500);
 }
 
 #[test]
@@ -49,6 +105,7 @@ fn primary_distribution_reward_rate_correct_calculation() {
 }
 
 #[test]
+#[allow(deprecated)]
 fn simple_successful_secondary_distribution() {
     let mut setup = ContractSetup::new(nft_staking::contract_obj);
     let transfers = vec![new_nft_transfer(POOL2_TOKEN_ID, 1, 1)];
@@ -56,6 +113,7 @@ fn simple_successful_secondary_distribution() {
     setup.set_stake_pool_type(POOL2_TOKEN_ID, StakingModuleType::SnakesSfts);
     setup.stake(&transfers, NO_ERR_MSG);
 
+    setup.b_mock.set_block_epoch(2);
     setup.register_token_as_eligible_reward_for_pool(
         SECONDARY_REWARD_TOKEN_ID_1,
         StakingModuleType::SnakesSfts,
@@ -77,6 +135,10 @@ fn secondary_distribution_reward_rate_correct_calculation() {
     let mut setup = ContractSetup::new(nft_staking::contract_obj);
     setup.set_stake_pool_type(POOL2_TOKEN_ID, StakingModuleType::SnakesSfts);
     setup.set_aggregated_score(StakingModuleType::SnakesSfts, aggregated_score);
+    setup.register_token_as_eligible_reward_for_pool(
+        SECONDARY_REWARD_TOKEN_ID_1,
+        StakingModuleType::SnakesSfts,
+    );
 
     let reward_rate = reward / aggregated_score;
 
@@ -101,6 +163,7 @@ fn secondary_distribution_reward_rate_correct_calculation() {
 /// The primary reward distribution is using the aggregated score of the staking module type "All"
 /// meanwhile the secondary reward distribution is using the aggregated score of the specific staking module
 #[test]
+#[allow(deprecated)]
 fn mixed_successful_distribution() {
     let primary_reward_amount = 100_000u64;
     let primary_aggregated_score = 100u64;
@@ -134,6 +197,7 @@ fn mixed_successful_distribution() {
     setup.stake(&primary_transfer, NO_ERR_MSG);
     setup.stake(&secondary_transfer, NO_ERR_MSG);
 
+    setup.b_mock.set_block_epoch(2);
     setup.set_aggregated_score(StakingModuleType::All, primary_aggregated_score);
     setup.set_aggregated_score(StakingModuleType::SnakesSfts, secondary_aggregated_score);
     setup.distribute_reward(primary_reward_amount, NO_ERR_MSG);
@@ -158,3 +222,346 @@ fn mixed_successful_distribution() {
     setup.assert_pending_reward(expected_primary_reward);
     setup.assert_explicit_pending_reward(SECONDARY_REWARD_TOKEN_ID_1, expected_secondary_reward);
 }
+
+#[test]
+#[allow(deprecated)]
+fn distribution_remainder_rolls_into_next_distribution() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&transfers, NO_ERR_MSG);
+    setup.set_aggregated_score(StakingModuleType::All, 3);
+
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(100, NO_ERR_MSG);
+    setup.assert_pending_reward(33);
+    setup.assert_reward_dust(StakingModuleType::All, REWARD_TOKEN_ID, 1);
+
+    setup.b_mock.set_block_epoch(3);
+    setup.distribute_reward(200, NO_ERR_MSG);
+    setup.assert_pending_reward(100);
+    setup.assert_reward_dust(StakingModuleType::All, REWARD_TOKEN_ID, 0);
+}
+
+#[test]
+#[allow(deprecated)]
+fn rewards_smaller_than_total_score_are_not_lost() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&transfers, NO_ERR_MSG);
+    setup.set_aggregated_score(StakingModuleType::All, 4);
+
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(2, NO_ERR_MSG);
+    setup.assert_pending_reward(0);
+    setup.assert_reward_dust(StakingModuleType::All, REWARD_TOKEN_ID, 0);
+
+    setup.b_mock.set_block_epoch(3);
+    setup.distribute_reward(2, NO_ERR_MSG);
+    setup.assert_pending_reward(1);
+}
+
+#[test]
+#[allow(deprecated)]
+fn zero_score_distribution_released_by_next_distribution() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.stake(&transfers, NO_ERR_MSG);
+    setup.assert_pending_reward(0);
+
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(50_000, NO_ERR_MSG);
+    setup.assert_pending_reward(150_000);
+}
+
+#[test]
+#[allow(deprecated)]
+fn zero_score_reserve_recovered_by_owner() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let owner_address = setup.owner_address.clone();
+
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup
+        .b_mock
+        .check_esdt_balance(&owner_address, REWARD_TOKEN_ID, &rust_biguint!(999_900_000));
+
+    setup.recover_zero_score_reserve(StakingModuleType::All, REWARD_TOKEN_ID, NO_ERR_MSG);
+    setup.b_mock.check_esdt_balance(
+        &owner_address,
+        REWARD_TOKEN_ID,
+        &rust_biguint!(1_000_000_000),
+    );
+    setup.recover_zero_score_reserve(
+        StakingModuleType::All,
+        REWARD_TOKEN_ID,
+        ERR_NOTHING_TO_CLAIM,
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn reward_schedule_released_per_epoch() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    setup.fund_reward_schedule(
+        StakingModuleType::All,
+        REWARD_TOKEN_ID,
+        1_000,
+        3,
+        NO_ERR_MSG,
+    );
+    setup.assert_reward_schedule_remaining_epochs(StakingModuleType::All, REWARD_TOKEN_ID, 3);
+
+    setup.b_mock.set_block_epoch(2);
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 333);
+    setup.assert_reward_rate(StakingModuleType::All, REWARD_TOKEN_ID, 2, 333);
+    setup.assert_reward_schedule_remaining_epochs(StakingModuleType::All, REWARD_TOKEN_ID, 2);
+
+    // missed epochs are released together, the last one includes the division remainder
+    setup.b_mock.set_block_epoch(10);
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 1_000);
+    setup.assert_reward_schedule_remaining_epochs(StakingModuleType::All, REWARD_TOKEN_ID, 0);
+}
+
+#[test]
+fn reward_schedule_requires_epochs() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+
+    setup.fund_reward_schedule(
+        StakingModuleType::All,
+        REWARD_TOKEN_ID,
+        1_000,
+        0,
+        ERR_INVALID_REWARD_SCHEDULE,
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn trigger_distribution_pulls_from_reservoir_once_per_epoch() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.stake(&transfers, NO_ERR_MSG);
+    setup.b_mock.set_block_epoch(2);
+
+    setup.trigger_distribution(
+        StakingModuleType::All,
+        REWARD_TOKEN_ID,
+        ERR_NOTHING_TO_DISTRIBUTE,
+    );
+    setup.deposit_reward_reservoir(StakingModuleType::All, REWARD_TOKEN_ID, 150, 100);
+
+    setup.trigger_distribution(StakingModuleType::All, REWARD_TOKEN_ID, NO_ERR_MSG);
+    setup.assert_reward_rate(StakingModuleType::All, REWARD_TOKEN_ID, 2, 100);
+    setup.trigger_distribution(
+        StakingModuleType::All,
+        REWARD_TOKEN_ID,
+        ERR_REWARD_ALREADY_DISTRIBUTED,
+    );
+
+    setup.b_mock.set_block_epoch(3);
+    setup.trigger_distribution(StakingModuleType::All, REWARD_TOKEN_ID, NO_ERR_MSG);
+    setup.assert_reward_rate(StakingModuleType::All, REWARD_TOKEN_ID, 3, 50);
+    setup.assert_pending_reward(150);
+
+    setup.b_mock.set_block_epoch(4);
+    setup.trigger_distribution(
+        StakingModuleType::All,
+        REWARD_TOKEN_ID,
+        ERR_NOTHING_TO_DISTRIBUTE,
+    );
+}
+
+#[test]
+fn weighted_distribution_splits_payment_across_modules() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_aggregated_score(StakingModuleType::All, 1);
+    setup.set_aggregated_score(StakingModuleType::SnakesSfts, 1);
+
+    setup.distribute_weighted(REWARD_TOKEN_ID, 1_001, ERR_INVALID_DISTRIBUTION_WEIGHTS);
+    setup.set_distribution_weights(
+        REWARD_TOKEN_ID,
+        &[
+            (StakingModuleType::All, 7_500),
+            (StakingModuleType::SnakesSfts, 2_500),
+        ],
+        ERR_INVALID_REWARD_TARGET,
+    );
+    setup
+        .register_token_as_eligible_reward_for_pool(REWARD_TOKEN_ID, StakingModuleType::SnakesSfts);
+    setup.set_distribution_weights(
+        REWARD_TOKEN_ID,
+        &[
+            (StakingModuleType::All, 7_500),
+            (StakingModuleType::SnakesSfts, 2_500),
+        ],
+        NO_ERR_MSG,
+    );
+
+    setup.distribute_weighted(REWARD_TOKEN_ID, 1_001, NO_ERR_MSG);
+    setup.assert_reward_rate(StakingModuleType::All, REWARD_TOKEN_ID, 1, 750);
+    setup.assert_reward_rate(StakingModuleType::SnakesSfts, REWARD_TOKEN_ID, 1, 251);
+
+    setup.distribute_weighted(REWARD_TOKEN_ID, 1_001, NO_ERR_MSG);
+    setup.assert_reward_rate(StakingModuleType::All, REWARD_TOKEN_ID, 1, 1_500);
+    setup.assert_reward_rate(StakingModuleType::SnakesSfts, REWARD_TOKEN_ID, 1, 502);
+}
+
+#[test]
+fn distribution_weights_must_add_up() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup
+        .register_token_as_eligible_reward_for_pool(REWARD_TOKEN_ID, StakingModuleType::SnakesSfts);
+    setup.set_distribution_weights(
+        REWARD_TOKEN_ID,
+        &[
+            (StakingModuleType::All, 7_500),
+            (StakingModuleType::SnakesSfts, 2_000),
+        ],
+        ERR_INVALID_DISTRIBUTION_WEIGHTS,
+    );
+    setup.set_distribution_weights(
+        REWARD_TOKEN_ID,
+        &[
+            (StakingModuleType::All, 5_000),
+            (StakingModuleType::All, 5_000),
+        ],
+        ERR_INVALID_DISTRIBUTION_WEIGHTS,
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn stake_right_before_distribution_does_not_earn_it() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_aggregated_score(StakingModuleType::All, 9);
+
+    setup.b_mock.set_block_epoch(2);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+    setup.distribute_reward(90_000, NO_ERR_MSG);
+    setup.assert_reward_rate(StakingModuleType::All, REWARD_TOKEN_ID, 2, 10_000);
+    setup.assert_pending_reward(0);
+
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(1, 1)], NO_ERR_MSG);
+    setup.claim_rewards(ERR_NOTHING_TO_CLAIM);
+}
+
+#[test]
+#[allow(deprecated)]
+fn score_added_during_epoch_earns_from_next_epoch() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+
+    setup.b_mock.set_block_epoch(2);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 2, 1)], NO_ERR_MSG);
+    setup.set_aggregated_score(StakingModuleType::All, 10);
+    setup.distribute_reward(90_000, NO_ERR_MSG);
+    setup.assert_pending_reward(10_000);
+
+    setup.b_mock.set_block_epoch(3);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.assert_pending_reward(30_000);
+}
+
+#[test]
+#[allow(deprecated)]
+fn unstaking_during_epoch_cancels_unsettled_score_first() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+
+    setup.b_mock.set_block_epoch(2);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 2, 1)], NO_ERR_MSG);
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(1, 1)], NO_ERR_MSG);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.assert_pending_reward(100_000);
+}
+
+#[test]
+#[allow(deprecated)]
+fn sft_rewards_paid_in_whole_units() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.register_reward_token(
+        SFT_REWARD_TOKEN_ID,
+        StakingModuleType::All,
+        Some(SFT_REWARD_TOKEN_NONCE),
+        NO_ERR_MSG,
+    );
+    // every nonce of the collection is a reward token of its own
+    setup.register_reward_token(
+        SFT_REWARD_TOKEN_ID,
+        StakingModuleType::All,
+        Some(SFT_REWARD_TOKEN_NONCE + 1),
+        NO_ERR_MSG,
+    );
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+
+    let owner_address = setup.owner_address.clone();
+    for reward_token_nonce in [SFT_REWARD_TOKEN_NONCE + 1, SFT_REWARD_TOKEN_NONCE + 2].iter() {
+        setup.b_mock.set_nft_balance(
+            &owner_address,
+            SFT_REWARD_TOKEN_ID,
+            *reward_token_nonce,
+            &rust_biguint!(4),
+            b"",
+        );
+    }
+    setup.b_mock.set_block_epoch(2);
+    setup.set_aggregated_score(StakingModuleType::All, 4);
+    setup.distribute_sft_reward(
+        SFT_REWARD_TOKEN_ID,
+        SFT_REWARD_TOKEN_NONCE + 2,
+        2,
+        ERR_INVALID_REWARD_TOKEN_ID,
+    );
+
+    // half an item is owed, it is carried forward instead of being paid or lost
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.distribute_sft_reward(SFT_REWARD_TOKEN_ID, SFT_REWARD_TOKEN_NONCE, 2, NO_ERR_MSG);
+    setup.distribute_sft_reward(
+        SFT_REWARD_TOKEN_ID,
+        SFT_REWARD_TOKEN_NONCE + 1,
+        2,
+        NO_ERR_MSG,
+    );
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 25_000);
+    setup.assert_user_nft_balance(SFT_REWARD_TOKEN_ID, SFT_REWARD_TOKEN_NONCE, 0);
+    setup.assert_user_nft_balance(SFT_REWARD_TOKEN_ID, SFT_REWARD_TOKEN_NONCE + 1, 0);
+
+    // the remainders of both nonces are kept apart
+    setup.b_mock.set_block_epoch(3);
+    setup.distribute_sft_reward(SFT_REWARD_TOKEN_ID, SFT_REWARD_TOKEN_NONCE, 2, NO_ERR_MSG);
+    setup.distribute_sft_reward(
+        SFT_REWARD_TOKEN_ID,
+        SFT_REWARD_TOKEN_NONCE + 1,
+        2,
+        NO_ERR_MSG,
+    );
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_nft_balance(SFT_REWARD_TOKEN_ID, SFT_REWARD_TOKEN_NONCE + 1, 1);
+    setup.assert_user_nft_balance(SFT_REWARD_TOKEN_ID, SFT_REWARD_TOKEN_NONCE, 1);
+}
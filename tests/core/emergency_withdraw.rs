@@ -0,0 +1,201 @@
+use multiversx_sc_scenario::{
+    managed_address, managed_biguint, managed_token_id_wrapped, rust_biguint,
+};
+use nft_staking::{
+    constants::{ERR_EMERGENCY_MODE, ERR_NOTHING_TO_CLAIM, ERR_NOT_EMERGENCY_MODE},
+    staking_modules::staking_module_type::StakingModuleType,
+    storage::user_data::UserDataStorageModule,
+};
+
+use crate::setup::{
+    constants::{
+        NO_ERR_MSG, POOL1_TOKEN_ID, POOL2_QUANTITY_PER_NONCE, POOL2_TOKEN_ID, REWARD_TOKEN_ID,
+    },
+    types::new_nft_transfer,
+    ContractSetup,
+};
+
+#[test]
+fn emergency_withdraw_requires_emergency_mode() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+
+    setup.emergency_withdraw(ERR_NOT_EMERGENCY_MODE);
+}
+
+#[test]
+fn emergency_withdraw_returns_staked_and_unbonding_assets() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_stake_pool_type(POOL2_TOKEN_ID, StakingModuleType::Bloodshed);
+    let transfers = vec![
+        new_nft_transfer(POOL1_TOKEN_ID, 1, 1),
+        new_nft_transfer(POOL1_TOKEN_ID, 2, 1),
+        new_nft_transfer(POOL2_TOKEN_ID, 1, 10),
+    ];
+    setup.stake(&transfers, NO_ERR_MSG);
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(2, 1)], NO_ERR_MSG);
+
+    setup.set_emergency_mode(true);
+    setup.emergency_withdraw(NO_ERR_MSG);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 1, 1);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 2, 1);
+    setup.assert_user_nft_balance(POOL2_TOKEN_ID, 1, POOL2_QUANTITY_PER_NONCE as u64);
+
+    setup.emergency_withdraw(ERR_NOTHING_TO_CLAIM);
+}
+
+#[test]
+#[allow(deprecated)]
+fn emergency_withdraw_forfeits_pending_rewards() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.assert_pending_reward(100_000);
+
+    setup.set_emergency_mode(true);
+    setup.emergency_withdraw(NO_ERR_MSG);
+    setup.assert_pending_reward(0);
+    setup.assert_user_score(StakingModuleType::All, 0);
+    setup.claim_rewards(ERR_NOTHING_TO_CLAIM);
+}
+
+#[test]
+#[allow(deprecated)]
+fn emergency_withdraw_removes_score_from_aggregates() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(
+        &[
+            new_nft_transfer(POOL1_TOKEN_ID, 1, 1),
+            new_nft_transfer(POOL1_TOKEN_ID, 2, 1),
+            new_nft_transfer(POOL1_TOKEN_ID, 3, 1),
+        ],
+        NO_ERR_MSG,
+    );
+    // settling a third of the reward per item leaves a remainder with the user
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(3, 1)], NO_ERR_MSG);
+    setup.assert_aggregated_score(StakingModuleType::All, 2);
+
+    setup.set_emergency_mode(true);
+    setup.emergency_withdraw(NO_ERR_MSG);
+    setup.assert_aggregated_score(StakingModuleType::All, 0);
+
+    let address = setup.user_address.clone();
+    setup
+        .b_mock
+        .execute_query(&setup.contract_wrapper, |sc| {
+            let user = managed_address!(&address);
+            let reward_token_id = managed_token_id_wrapped!(REWARD_TOKEN_ID);
+            assert!(sc
+                .unsettled_aggregated_score(&StakingModuleType::All)
+                .get()
+                .amount
+                .eq(&0u64));
+            assert!(sc
+                .pending_reward_remainder(&user, &reward_token_id, 0)
+                .is_empty());
+            assert!(sc
+                .user_reward_per_share(&StakingModuleType::All, &reward_token_id, 0, &user)
+                .is_empty());
+            assert!(sc.total_reward_remainder(&reward_token_id, 0).get() == 0u64);
+        })
+        .assert_ok();
+}
+
+#[test]
+#[allow(deprecated)]
+fn emergency_mode_blocks_staking_and_distributions() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(1, 1)], NO_ERR_MSG);
+
+    setup.set_emergency_mode(true);
+    setup.stake(
+        &[new_nft_transfer(POOL1_TOKEN_ID, 2, 1)],
+        ERR_EMERGENCY_MODE,
+    );
+    setup.cancel_unbonding(1, ERR_EMERGENCY_MODE);
+    setup.distribute_reward(100_000, ERR_EMERGENCY_MODE);
+
+    setup.set_emergency_mode(false);
+    setup.cancel_unbonding(1, NO_ERR_MSG);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+}
+
+#[test]
+#[allow(deprecated)]
+fn emergency_withdraw_tolerates_corrupted_aggregates() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(
+        &[
+            new_nft_transfer(POOL1_TOKEN_ID, 1, 1),
+            new_nft_transfer(POOL1_TOKEN_ID, 2, 1),
+            new_nft_transfer(POOL1_TOKEN_ID, 3, 1),
+        ],
+        NO_ERR_MSG,
+    );
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(3, 1)], NO_ERR_MSG);
+
+    // aggregates lower than the user's own share must not block the withdrawal
+    setup.set_aggregated_score(StakingModuleType::All, 0);
+    setup
+        .b_mock
+        .execute_tx(
+            &setup.owner_address,
+            &setup.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let reward_token_id = managed_token_id_wrapped!(REWARD_TOKEN_ID);
+                sc.unsettled_aggregated_score(&StakingModuleType::All)
+                    .update(|unsettled_score| unsettled_score.amount = managed_biguint!(0));
+                sc.total_reward_remainder(&reward_token_id, 0).clear();
+            },
+        )
+        .assert_ok();
+
+    setup.set_emergency_mode(true);
+    setup.emergency_withdraw(NO_ERR_MSG);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 1, 1);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 3, 1);
+    setup.assert_aggregated_score(StakingModuleType::All, 0);
+}
+
+#[test]
+#[allow(deprecated)]
+fn reward_schedule_held_in_emergency_mode() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+    setup.fund_reward_schedule(
+        StakingModuleType::All,
+        REWARD_TOKEN_ID,
+        1_000,
+        3,
+        NO_ERR_MSG,
+    );
+
+    setup.set_emergency_mode(true);
+    setup.b_mock.set_block_epoch(2);
+    setup.claim_rewards(ERR_NOTHING_TO_CLAIM);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 0);
+
+    setup.set_emergency_mode(false);
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 333);
+    setup.assert_reward_schedule_remaining_epochs(StakingModuleType::All, REWARD_TOKEN_ID, 2);
+}
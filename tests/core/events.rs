@@ -0,0 +1,198 @@
+use multiversx_sc::{codec::TopDecode, types::ManagedBuffer};
+use multiversx_sc_scenario::{
+    managed_address, managed_biguint, managed_token_id, managed_token_id_wrapped, rust_biguint,
+    DebugApi,
+};
+use nft_staking::{
+    owner::OwnerModule,
+    pause::PauseModule,
+    staking_modules::staking_module_type::StakingModuleType,
+    types::event_data::{ForceUnstakeEventData, StakingEventData},
+    NftStakingContract,
+};
+
+use crate::setup::{
+    constants::{NO_ERR_MSG, POOL1_TOKEN_ID, POOL2_TOKEN_ID, SECONDARY_REWARD_TOKEN_ID_1},
+    types::new_nft_transfer,
+    ContractSetup,
+};
+
+#[test]
+#[allow(deprecated)]
+fn stake_emits_event() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    let user_address = setup.user_address.clone();
+
+    let tx_result = setup.b_mock.execute_esdt_transfer(
+        &user_address,
+        &setup.contract_wrapper,
+        POOL1_TOKEN_ID,
+        1,
+        &rust_biguint!(1),
+        |sc| {
+            sc.stake();
+        },
+    );
+    tx_result.assert_ok();
+
+    let stake_log = tx_result
+        .result_logs
+        .iter()
+        .find(|log| log.topics[0] == b"stake".to_vec())
+        .expect("stake event not emitted");
+    assert_eq!(stake_log.topics[1], user_address.to_vec());
+    assert_eq!(stake_log.topics[2], POOL1_TOKEN_ID.to_vec());
+}
+
+#[test]
+#[allow(deprecated)]
+fn claim_rewards_emits_event() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    let user_address = setup.user_address.clone();
+
+    let tx_result = setup.b_mock.execute_tx(
+        &user_address,
+        &setup.contract_wrapper,
+        &rust_biguint!(0),
+        |sc| {
+            sc.claim_rewards();
+        },
+    );
+    tx_result.assert_ok();
+
+    assert!(tx_result
+        .result_logs
+        .iter()
+        .any(|log| log.topics[0] == b"claimRewards".to_vec()
+            && log.topics[1] == user_address.to_vec()));
+}
+
+#[test]
+#[allow(deprecated)]
+fn snakes_stake_event_carries_shares_score_update() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_stake_pool_type(POOL2_TOKEN_ID, StakingModuleType::SnakesSfts);
+    setup.set_token_score(StakingModuleType::SnakesSfts, POOL2_TOKEN_ID, 1);
+    setup.set_token_nonce_score(StakingModuleType::SharesSfts, POOL2_TOKEN_ID, 1, 3);
+    let user_address = setup.user_address.clone();
+
+    let tx_result = setup.b_mock.execute_esdt_transfer(
+        &user_address,
+        &setup.contract_wrapper,
+        POOL2_TOKEN_ID,
+        1,
+        &rust_biguint!(2),
+        |sc| {
+            sc.stake();
+        },
+    );
+    tx_result.assert_ok();
+
+    let stake_log = tx_result
+        .result_logs
+        .iter()
+        .find(|log| log.topics[0] == b"stake".to_vec())
+        .expect("stake event not emitted");
+    setup
+        .b_mock
+        .execute_query(&setup.contract_wrapper, |_| {
+            let event_data = StakingEventData::<DebugApi>::top_decode(
+                ManagedBuffer::<DebugApi>::new_from_bytes(&stake_log.data),
+            )
+            .unwrap();
+            let shares_score_update = event_data.shares_score_update.unwrap();
+            assert_eq!(shares_score_update.previous_user_score, managed_biguint!(0));
+            assert_eq!(shares_score_update.user_score, managed_biguint!(6));
+            assert_eq!(shares_score_update.aggregated_score, managed_biguint!(6));
+        })
+        .assert_ok();
+}
+
+#[test]
+#[allow(deprecated)]
+fn force_unstake_event_carries_score_updates() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+    let user_address = setup.user_address.clone();
+
+    let tx_result = setup.b_mock.execute_tx(
+        &setup.owner_address,
+        &setup.contract_wrapper,
+        &rust_biguint!(0),
+        |sc| {
+            sc.force_unstake(
+                managed_address!(&user_address),
+                managed_token_id!(POOL1_TOKEN_ID),
+            );
+        },
+    );
+    tx_result.assert_ok();
+
+    let force_unstake_log = tx_result
+        .result_logs
+        .iter()
+        .find(|log| log.topics[0] == b"forceUnstake".to_vec())
+        .expect("forceUnstake event not emitted");
+    setup
+        .b_mock
+        .execute_query(&setup.contract_wrapper, |_| {
+            let event_data = ForceUnstakeEventData::<DebugApi>::top_decode(
+                ManagedBuffer::<DebugApi>::new_from_bytes(&force_unstake_log.data),
+            )
+            .unwrap();
+            assert_eq!(event_data.payments.len(), 1);
+            let primary_score_update = event_data.staking_event_data.unwrap().primary_score_update;
+            assert_eq!(
+                primary_score_update.previous_user_score,
+                managed_biguint!(1)
+            );
+            assert_eq!(primary_score_update.user_score, managed_biguint!(0));
+            assert_eq!(primary_score_update.aggregated_score, managed_biguint!(0));
+        })
+        .assert_ok();
+}
+
+#[test]
+#[allow(deprecated)]
+fn configuration_changes_emit_events() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let owner_address = setup.owner_address.clone();
+
+    let tx_result = setup.b_mock.execute_tx(
+        &owner_address,
+        &setup.contract_wrapper,
+        &rust_biguint!(0),
+        |sc| {
+            sc.pause_pool(managed_token_id!(POOL1_TOKEN_ID));
+            sc.register_reward_token(
+                managed_token_id_wrapped!(SECONDARY_REWARD_TOKEN_ID_1),
+                StakingModuleType::XBunnies,
+                None.into(),
+            );
+        },
+    );
+    tx_result.assert_ok();
+
+    let pause_pool_log = tx_result
+        .result_logs
+        .iter()
+        .find(|log| log.topics[0] == b"pausePool".to_vec())
+        .expect("pausePool event not emitted");
+    assert_eq!(pause_pool_log.topics[1], owner_address.to_vec());
+    assert_eq!(pause_pool_log.topics[2], POOL1_TOKEN_ID.to_vec());
+
+    let register_log = tx_result
+        .result_logs
+        .iter()
+        .find(|log| log.topics[0] == b"registerRewardToken".to_vec())
+        .expect("registerRewardToken event not emitted");
+    assert_eq!(register_log.topics[1], SECONDARY_REWARD_TOKEN_ID_1.to_vec());
+}
@@ -0,0 +1,56 @@
+use nft_staking::{
+    constants::ERR_NOTHING_TO_UNSTAKE, staking_modules::staking_module_type::StakingModuleType,
+};
+
+use crate::setup::{
+    constants::{NO_ERR_MSG, POOL1_TOKEN_ID, POOL2_TOKEN_ID},
+    types::new_nft_transfer,
+    ContractSetup,
+};
+
+#[test]
+fn force_unstake_subtracts_only_user_contribution() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_token_score(StakingModuleType::XBunnies, POOL1_TOKEN_ID, 2);
+    setup.set_aggregated_score(StakingModuleType::All, 100);
+    setup.set_aggregated_score(StakingModuleType::XBunnies, 200);
+
+    let transfers = vec![
+        new_nft_transfer(POOL1_TOKEN_ID, 1, 1),
+        new_nft_transfer(POOL1_TOKEN_ID, 2, 1),
+    ];
+    setup.stake(&transfers, NO_ERR_MSG);
+    setup.assert_aggregated_score(StakingModuleType::All, 102);
+
+    setup.force_unstake(POOL1_TOKEN_ID, NO_ERR_MSG);
+    setup.assert_user_score(StakingModuleType::All, 0);
+    setup.assert_user_score(StakingModuleType::XBunnies, 0);
+    setup.assert_aggregated_score(StakingModuleType::All, 100);
+    setup.assert_aggregated_score(StakingModuleType::XBunnies, 200);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 1, 1);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 2, 1);
+}
+
+#[test]
+fn force_unstake_returns_unbonding_assets_of_token() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_stake_pool_type(POOL2_TOKEN_ID, StakingModuleType::Bloodshed);
+    let transfers = vec![
+        new_nft_transfer(POOL1_TOKEN_ID, 1, 1),
+        new_nft_transfer(POOL1_TOKEN_ID, 2, 1),
+        new_nft_transfer(POOL2_TOKEN_ID, 1, 1),
+    ];
+    setup.stake(&transfers, NO_ERR_MSG);
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(1, 1)], NO_ERR_MSG);
+    setup.start_unbonding(POOL2_TOKEN_ID, &[(1, 1)], NO_ERR_MSG);
+
+    setup.force_unstake(POOL1_TOKEN_ID, NO_ERR_MSG);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 1, 1);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 2, 1);
+
+    setup.force_unstake(POOL1_TOKEN_ID, ERR_NOTHING_TO_UNSTAKE);
+    setup.cancel_unbonding(2, NO_ERR_MSG);
+}
@@ -0,0 +1,200 @@
+use multiversx_sc_scenario::{managed_token_id_wrapped, rust_biguint};
+use nft_staking::{
+    constants::{ERR_INSTANT_UNSTAKE_DISABLED, ERR_INVALID_FEE_PAYMENT},
+    staking_modules::staking_module_type::StakingModuleType,
+    storage::user_data::UserDataStorageModule,
+    types::instant_unstake_fee::FeeDestination,
+};
+
+use crate::setup::{
+    constants::{NO_ERR_MSG, POOL1_TOKEN_ID, REWARD_TOKEN_ID, SECONDARY_REWARD_TOKEN_ID_2},
+    types::new_nft_transfer,
+    ContractSetup,
+};
+
+#[test]
+fn instant_unstake_disabled_by_default() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    setup.instant_unstake(POOL1_TOKEN_ID, &[(1, 1)], &[], ERR_INSTANT_UNSTAKE_DISABLED);
+}
+
+#[test]
+#[allow(deprecated)]
+fn instant_unstake_payment_fee_sent_to_treasury() {
+    let fee_amount = 1_000;
+
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let treasury_address = setup.b_mock.create_user_account(&rust_biguint!(0));
+    let user_address = setup.user_address.clone();
+    setup.b_mock.set_esdt_balance(
+        &user_address,
+        SECONDARY_REWARD_TOKEN_ID_2,
+        &rust_biguint!(fee_amount),
+    );
+
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    setup.set_treasury_address(&treasury_address);
+    setup.set_instant_unstake_payment_fee(
+        POOL1_TOKEN_ID,
+        SECONDARY_REWARD_TOKEN_ID_2,
+        fee_amount,
+        FeeDestination::Treasury,
+    );
+
+    let fee_transfer = new_nft_transfer(SECONDARY_REWARD_TOKEN_ID_2, 0, fee_amount);
+    setup.instant_unstake(POOL1_TOKEN_ID, &[(1, 1)], &[fee_transfer], NO_ERR_MSG);
+
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 1, 1);
+    setup.assert_user_score(StakingModuleType::All, 0);
+    setup.assert_aggregated_score(StakingModuleType::All, 0);
+    setup.b_mock.check_esdt_balance(
+        &treasury_address,
+        SECONDARY_REWARD_TOKEN_ID_2,
+        &rust_biguint!(fee_amount),
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn instant_unstake_payment_fee_added_to_next_distribution() {
+    let fee_amount = 1_000;
+    let reward = 100_000;
+
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let user_address = setup.user_address.clone();
+    setup
+        .b_mock
+        .set_esdt_balance(&user_address, REWARD_TOKEN_ID, &rust_biguint!(fee_amount));
+
+    let transfers = vec![
+        new_nft_transfer(POOL1_TOKEN_ID, 1, 1),
+        new_nft_transfer(POOL1_TOKEN_ID, 2, 1),
+    ];
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_token_score(StakingModuleType::XBunnies, POOL1_TOKEN_ID, 1);
+    setup.register_token_as_eligible_reward_for_pool(REWARD_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    setup.b_mock.set_block_epoch(2);
+    setup.set_instant_unstake_payment_fee(
+        POOL1_TOKEN_ID,
+        REWARD_TOKEN_ID,
+        fee_amount,
+        FeeDestination::Stakers,
+    );
+
+    let fee_transfer = new_nft_transfer(REWARD_TOKEN_ID, 0, fee_amount);
+    setup.instant_unstake(POOL1_TOKEN_ID, &[(1, 1)], &[fee_transfer], NO_ERR_MSG);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 1, 1);
+
+    setup
+        .b_mock
+        .execute_query(&setup.contract_wrapper, |sc| {
+            let undistributed_rewards = sc
+                .undistributed_rewards(
+                    &StakingModuleType::XBunnies,
+                    &managed_token_id_wrapped!(REWARD_TOKEN_ID),
+                    0,
+                )
+                .get();
+            assert_eq!(undistributed_rewards, fee_amount);
+        })
+        .assert_ok();
+
+    setup.distribute_secondary_reward(REWARD_TOKEN_ID, POOL1_TOKEN_ID, reward, NO_ERR_MSG);
+    setup.assert_reward_rate(
+        StakingModuleType::XBunnies,
+        REWARD_TOKEN_ID,
+        2,
+        reward + fee_amount,
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn instant_unstake_fee_charged_after_claiming_rewards() {
+    let fee_amount = 1_000;
+    let reward = 100_000;
+
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let treasury_address = setup.b_mock.create_user_account(&rust_biguint!(0));
+    let user_address = setup.user_address.clone();
+    setup.b_mock.set_esdt_balance(
+        &user_address,
+        SECONDARY_REWARD_TOKEN_ID_2,
+        &rust_biguint!(fee_amount),
+    );
+
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.stake(&transfers, NO_ERR_MSG);
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(reward, NO_ERR_MSG);
+
+    setup.set_treasury_address(&treasury_address);
+    setup.set_instant_unstake_payment_fee(
+        POOL1_TOKEN_ID,
+        SECONDARY_REWARD_TOKEN_ID_2,
+        fee_amount,
+        FeeDestination::Treasury,
+    );
+
+    // emptying the pending rewards first does not waive the fee
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, reward);
+    setup.instant_unstake(POOL1_TOKEN_ID, &[(1, 1)], &[], ERR_INVALID_FEE_PAYMENT);
+
+    let fee_transfer = new_nft_transfer(SECONDARY_REWARD_TOKEN_ID_2, 0, fee_amount);
+    setup.instant_unstake(POOL1_TOKEN_ID, &[(1, 1)], &[fee_transfer], NO_ERR_MSG);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 1, 1);
+    setup.b_mock.check_esdt_balance(
+        &treasury_address,
+        SECONDARY_REWARD_TOKEN_ID_2,
+        &rust_biguint!(fee_amount),
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn instant_unstake_wrong_fee_payment_fails() {
+    let fee_amount = 1_000;
+
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let treasury_address = setup.b_mock.create_user_account(&rust_biguint!(0));
+    let user_address = setup.user_address.clone();
+    setup.b_mock.set_esdt_balance(
+        &user_address,
+        SECONDARY_REWARD_TOKEN_ID_2,
+        &rust_biguint!(fee_amount),
+    );
+
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    setup.set_treasury_address(&treasury_address);
+    setup.set_instant_unstake_payment_fee(
+        POOL1_TOKEN_ID,
+        SECONDARY_REWARD_TOKEN_ID_2,
+        fee_amount,
+        FeeDestination::Treasury,
+    );
+
+    let fee_transfer = new_nft_transfer(SECONDARY_REWARD_TOKEN_ID_2, 0, fee_amount / 2);
+    setup.instant_unstake(
+        POOL1_TOKEN_ID,
+        &[(1, 1)],
+        &[fee_transfer],
+        ERR_INVALID_FEE_PAYMENT,
+    );
+    setup.instant_unstake(POOL1_TOKEN_ID, &[(1, 1)], &[], ERR_INVALID_FEE_PAYMENT);
+}
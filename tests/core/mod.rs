@@ -1,5 +1,13 @@
 mod claim_rewards;
 mod distribute_reward;
+mod emergency_withdraw;
+mod events;
+mod force_unstake;
+mod instant_unstake;
+mod pause;
+mod reward_registry;
+mod reward_retirement;
 mod score_update;
 mod stake;
 mod unbonding;
+mod vesting;
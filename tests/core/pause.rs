@@ -0,0 +1,102 @@
+use multiversx_sc_scenario::rust_biguint;
+use nft_staking::{
+    constants::{
+        DEFAULT_UNBONDING_TIME_PENALTY, ERR_CONTRACT_PAUSED, ERR_NOT_OWNER_OR_PAUSER,
+        ERR_POOL_PAUSED,
+    },
+    staking_modules::staking_module_type::StakingModuleType,
+};
+
+use crate::setup::{
+    constants::{NO_ERR_MSG, POOL1_TOKEN_ID, POOL2_TOKEN_ID},
+    types::new_nft_transfer,
+    ContractSetup,
+};
+
+#[test]
+fn paused_contract_rejects_stake_and_unbonding() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let owner_address = setup.owner_address.clone();
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+
+    setup.pause(&owner_address, NO_ERR_MSG);
+    setup.stake(
+        &[new_nft_transfer(POOL1_TOKEN_ID, 2, 1)],
+        ERR_CONTRACT_PAUSED,
+    );
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(1, 1)], ERR_CONTRACT_PAUSED);
+
+    setup.unpause(&owner_address, NO_ERR_MSG);
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(1, 1)], NO_ERR_MSG);
+}
+
+#[test]
+#[allow(deprecated)]
+fn paused_contract_allows_claim_unbonded() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let owner_address = setup.owner_address.clone();
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(1, 1)], NO_ERR_MSG);
+
+    setup.pause(&owner_address, NO_ERR_MSG);
+    setup
+        .b_mock
+        .set_block_timestamp(DEFAULT_UNBONDING_TIME_PENALTY + 1);
+    setup.claim_unbonded(NO_ERR_MSG);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 1, 1);
+}
+
+#[test]
+fn paused_pool_only_rejects_its_collection() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let owner_address = setup.owner_address.clone();
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_stake_pool_type(POOL2_TOKEN_ID, StakingModuleType::Bloodshed);
+
+    setup.pause_pool(&owner_address, POOL1_TOKEN_ID, NO_ERR_MSG);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], ERR_POOL_PAUSED);
+    setup.stake(&[new_nft_transfer(POOL2_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+
+    let transfers = vec![
+        new_nft_transfer(POOL2_TOKEN_ID, 2, 1),
+        new_nft_transfer(POOL1_TOKEN_ID, 1, 1),
+    ];
+    setup.stake(&transfers, ERR_POOL_PAUSED);
+}
+
+#[test]
+#[allow(deprecated)]
+fn pauser_role_can_pause() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let pauser_address = setup.b_mock.create_user_account(&rust_biguint!(0));
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+
+    setup.pause(&pauser_address, ERR_NOT_OWNER_OR_PAUSER);
+    setup.add_pauser(&pauser_address);
+    setup.pause(&pauser_address, NO_ERR_MSG);
+    setup.stake(
+        &[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)],
+        ERR_CONTRACT_PAUSED,
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn claim_rewards_pause_is_configurable() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let owner_address = setup.owner_address.clone();
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+
+    setup.pause(&owner_address, NO_ERR_MSG);
+    setup.set_claim_rewards_pausable(true);
+    setup.claim_rewards(ERR_CONTRACT_PAUSED);
+
+    setup.set_claim_rewards_pausable(false);
+    setup.claim_rewards(NO_ERR_MSG);
+}
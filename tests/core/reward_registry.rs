@@ -0,0 +1,157 @@
+use multiversx_sc::types::MultiValueEncoded;
+use multiversx_sc_scenario::{
+    managed_address, managed_biguint, managed_token_id_wrapped, rust_biguint,
+};
+use nft_staking::{
+    staking_modules::staking_module_type::StakingModuleType,
+    storage::{config::ConfigModule, user_data::UserDataStorageModule},
+    NftStakingContract,
+};
+
+use crate::setup::{
+    constants::{
+        NO_ERR_MSG, POOL1_TOKEN_ID, REWARD_TOKEN_ID, SECONDARY_REWARD_TOKEN_ID_1,
+        SECONDARY_REWARD_TOKEN_ID_2,
+    },
+    types::new_nft_transfer,
+    ContractSetup,
+};
+
+#[test]
+#[allow(deprecated)]
+fn primary_and_secondary_settlement_checkpoints_stay_consistent() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_token_score(StakingModuleType::XBunnies, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.register_token_as_eligible_reward_for_pool(REWARD_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.distribute_secondary_reward(REWARD_TOKEN_ID, POOL1_TOKEN_ID, 50_000, NO_ERR_MSG);
+
+    // both modules are settled into a single pending reward of the token
+    let address = setup.user_address.clone();
+    setup
+        .b_mock
+        .execute_query(&setup.contract_wrapper, |sc| {
+            let pending_rewards = sc.get_pending_reward(managed_address!(&address));
+            assert_eq!(pending_rewards.len(), 1);
+            assert_eq!(pending_rewards.get(0).amount, managed_biguint!(150_000));
+        })
+        .assert_ok();
+
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 2, 1)], NO_ERR_MSG);
+    setup.assert_stored_rewards(150_000);
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 150_000);
+
+    setup.b_mock.set_block_epoch(3);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 250_000);
+
+    setup
+        .b_mock
+        .execute_query(&setup.contract_wrapper, |sc| {
+            let reward_token_id = managed_token_id_wrapped!(REWARD_TOKEN_ID);
+            for staking_module in [StakingModuleType::All, StakingModuleType::XBunnies].iter() {
+                assert_eq!(
+                    sc.user_reward_per_share(
+                        staking_module,
+                        &reward_token_id,
+                        0,
+                        &managed_address!(&address)
+                    )
+                    .get(),
+                    sc.reward_per_share(staking_module, &reward_token_id, 0)
+                        .get()
+                );
+            }
+
+            let reward_token = sc.get_reward_token(&reward_token_id, 0);
+            assert_eq!(reward_token.total_distributed, managed_biguint!(250_000));
+            assert_eq!(reward_token.total_claimed, managed_biguint!(250_000));
+        })
+        .assert_ok();
+}
+
+#[test]
+#[allow(deprecated)]
+fn legacy_reward_token_sets_migrated_on_upgrade() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_aggregated_score(StakingModuleType::All, 1);
+
+    // state of a contract deployed before the registry: the primary token is only part of
+    // `reward_token_identifiers`, secondary rewards were distributed per epoch and the modules
+    // rewarded through `reward_rate` are listed by the owner on upgrade
+    setup
+        .b_mock
+        .execute_tx(
+            &setup.owner_address,
+            &setup.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let reward_token_id = managed_token_id_wrapped!(REWARD_TOKEN_ID);
+                let secondary_reward_token_id_1 =
+                    managed_token_id_wrapped!(SECONDARY_REWARD_TOKEN_ID_1);
+                let secondary_reward_token_id_2 =
+                    managed_token_id_wrapped!(SECONDARY_REWARD_TOKEN_ID_2);
+                sc.reward_registry().remove(&(reward_token_id.clone(), 0));
+                sc.legacy_reward_epoch_end().clear();
+
+                sc.reward_token_identifiers().insert(reward_token_id);
+                sc.reward_token_identifiers()
+                    .insert(secondary_reward_token_id_2.clone());
+                sc.reward_token_id_mapping()
+                    .insert((secondary_reward_token_id_1, StakingModuleType::XBunnies));
+            },
+        )
+        .assert_ok();
+
+    setup
+        .b_mock
+        .execute_tx(
+            &setup.owner_address,
+            &setup.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut legacy_reward_targets = MultiValueEncoded::new();
+                legacy_reward_targets.push(
+                    (
+                        managed_token_id_wrapped!(SECONDARY_REWARD_TOKEN_ID_2),
+                        StakingModuleType::SnakesSfts,
+                    )
+                        .into(),
+                );
+                sc.init(
+                    managed_token_id_wrapped!(REWARD_TOKEN_ID),
+                    legacy_reward_targets,
+                );
+            },
+        )
+        .assert_ok();
+
+    setup
+        .b_mock
+        .execute_query(&setup.contract_wrapper, |sc| {
+            assert!(sc.reward_token_identifiers().is_empty());
+            assert!(sc.reward_token_id_mapping().is_empty());
+            assert_eq!(sc.reward_registry().len(), 3);
+
+            let expected_targets = [
+                (REWARD_TOKEN_ID, StakingModuleType::All),
+                (SECONDARY_REWARD_TOKEN_ID_1, StakingModuleType::XBunnies),
+                (SECONDARY_REWARD_TOKEN_ID_2, StakingModuleType::SnakesSfts),
+            ];
+            for (token_id, staking_module) in expected_targets.iter() {
+                let reward_token = sc.get_reward_token(&managed_token_id_wrapped!(*token_id), 0);
+                assert_eq!(reward_token.staking_modules.len(), 1);
+                assert!(reward_token.targets(staking_module));
+            }
+        })
+        .assert_ok();
+
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+}
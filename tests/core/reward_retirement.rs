@@ -0,0 +1,142 @@
+use multiversx_sc_scenario::{managed_token_id_wrapped, rust_biguint};
+use nft_staking::{
+    constants::{
+        ERR_INVALID_SWEEP_DEADLINE, ERR_NOTHING_TO_CLAIM, ERR_REWARDS_NOT_CLAIMED,
+        ERR_REWARD_TOKEN_NOT_RETIRED, ERR_REWARD_TOKEN_RETIRED,
+    },
+    staking_modules::staking_module_type::StakingModuleType,
+    storage::{config::ConfigModule, user_data::UserDataStorageModule},
+};
+
+use crate::setup::{
+    constants::{NO_ERR_MSG, POOL1_TOKEN_ID, REWARD_TOKEN_ID},
+    types::new_nft_transfer,
+    ContractSetup,
+};
+
+const OWNER_REWARD_BALANCE: u64 = 1_000_000_000;
+
+#[test]
+#[allow(deprecated)]
+fn retired_reward_token_stays_claimable_until_swept() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.sweep_retired_reward_token(REWARD_TOKEN_ID, ERR_REWARD_TOKEN_NOT_RETIRED);
+    setup.retire_reward_token(REWARD_TOKEN_ID, 2, ERR_INVALID_SWEEP_DEADLINE);
+    setup.retire_reward_token(REWARD_TOKEN_ID, 10, NO_ERR_MSG);
+    setup.distribute_reward(100_000, ERR_REWARD_TOKEN_RETIRED);
+    setup.sweep_retired_reward_token(REWARD_TOKEN_ID, ERR_REWARDS_NOT_CLAIMED);
+
+    setup.b_mock.set_block_epoch(3);
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 100_000);
+
+    setup.sweep_retired_reward_token(REWARD_TOKEN_ID, NO_ERR_MSG);
+    setup
+        .b_mock
+        .execute_query(&setup.contract_wrapper, |sc| {
+            let reward_token_id = managed_token_id_wrapped!(REWARD_TOKEN_ID);
+            assert!(!sc
+                .reward_registry()
+                .contains_key(&(reward_token_id.clone(), 0)));
+            assert_eq!(sc.reward_token_generation(&reward_token_id, 0).get(), 1);
+        })
+        .assert_ok();
+    setup.register_reward_token(REWARD_TOKEN_ID, StakingModuleType::All, None, NO_ERR_MSG);
+}
+
+#[test]
+#[allow(deprecated)]
+fn unclaimed_rewards_swept_after_deadline() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.retire_reward_token(REWARD_TOKEN_ID, 5, NO_ERR_MSG);
+
+    setup.b_mock.set_block_epoch(4);
+    setup.sweep_retired_reward_token(REWARD_TOKEN_ID, ERR_REWARDS_NOT_CLAIMED);
+
+    setup.b_mock.set_block_epoch(5);
+    setup.sweep_retired_reward_token(REWARD_TOKEN_ID, NO_ERR_MSG);
+    setup.b_mock.check_esdt_balance(
+        &setup.owner_address,
+        REWARD_TOKEN_ID,
+        &rust_biguint!(OWNER_REWARD_BALANCE),
+    );
+
+    setup.claim_rewards(ERR_NOTHING_TO_CLAIM);
+
+    // the swept rewards are not paid out again once the token is registered anew
+    setup.register_reward_token(REWARD_TOKEN_ID, StakingModuleType::All, None, NO_ERR_MSG);
+    setup.b_mock.set_block_epoch(6);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 100_000);
+}
+
+#[test]
+#[allow(deprecated)]
+fn retired_reward_token_swept_early_once_only_dust_is_left() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let first_staker = setup.user_address.clone();
+    let second_staker = setup.create_staker();
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+    setup.user_address = second_staker.clone();
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 2, 1)], NO_ERR_MSG);
+
+    // each staker is owed one and a half units, half a unit stays behind with each of them
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(3, NO_ERR_MSG);
+    setup.retire_reward_token(REWARD_TOKEN_ID, 10, NO_ERR_MSG);
+
+    setup.b_mock.set_block_epoch(3);
+    setup.user_address = first_staker;
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 1);
+    setup.sweep_retired_reward_token(REWARD_TOKEN_ID, ERR_REWARDS_NOT_CLAIMED);
+
+    setup.user_address = second_staker;
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 1);
+    setup.sweep_retired_reward_token(REWARD_TOKEN_ID, NO_ERR_MSG);
+    setup.b_mock.check_esdt_balance(
+        &setup.owner_address,
+        REWARD_TOKEN_ID,
+        &rust_biguint!(OWNER_REWARD_BALANCE - 2),
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn legacy_reward_token_swept_at_deadline_only() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+    setup.set_legacy_reward_position(2, 5, 1_000);
+
+    // nothing was distributed since the upgrade, the legacy rewards are still owed
+    setup.b_mock.set_block_epoch(6);
+    setup.retire_reward_token(REWARD_TOKEN_ID, 10, NO_ERR_MSG);
+    setup.sweep_retired_reward_token(REWARD_TOKEN_ID, ERR_REWARDS_NOT_CLAIMED);
+
+    setup.b_mock.set_block_epoch(10);
+    setup.sweep_retired_reward_token(REWARD_TOKEN_ID, NO_ERR_MSG);
+    setup
+        .b_mock
+        .execute_query(&setup.contract_wrapper, |sc| {
+            assert!(sc.legacy_reward_tokens().is_empty());
+        })
+        .assert_ok();
+}
@@ -9,12 +9,14 @@ use crate::setup::{
 };
 
 #[test]
+#[allow(deprecated)]
 fn update_deb_triggers_secure_rewards() {
     let mut setup = ContractSetup::new(nft_staking::contract_obj);
     let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
     setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
     setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
     setup.stake(&transfers, NO_ERR_MSG);
+    setup.b_mock.set_block_epoch(2);
     setup.set_aggregated_score(StakingModuleType::All, 10);
     setup.distribute_reward(100_000, NO_ERR_MSG);
     setup.assert_pending_reward(10_000);
@@ -1,6 +1,4 @@
-use nft_staking::{
-    constants::ERR_ONE_TOKEN_ID_SUPPORTED, staking_modules::staking_module_type::StakingModuleType,
-};
+use nft_staking::staking_modules::staking_module_type::StakingModuleType;
 
 use crate::setup::{
     constants::{NO_ERR_MSG, POOL1_TOKEN_ID, POOL2_TOKEN_ID},
@@ -31,16 +29,45 @@ fn multiple_transfer_successful_stake() {
 }
 
 #[test]
-fn different_token_id_failed_stake() {
+fn different_token_id_successful_stake() {
     let mut setup = ContractSetup::new(nft_staking::contract_obj);
     setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_stake_pool_type(POOL2_TOKEN_ID, StakingModuleType::Bloodshed);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_token_score(StakingModuleType::All, POOL2_TOKEN_ID, 10);
+    setup.set_token_score(StakingModuleType::Bloodshed, POOL2_TOKEN_ID, 5);
 
     let transfers = vec![
         new_nft_transfer(POOL1_TOKEN_ID, 1, 1),
-        new_nft_transfer(POOL1_TOKEN_ID, 2, 1),
         new_nft_transfer(POOL2_TOKEN_ID, 3, 1),
+        new_nft_transfer(POOL1_TOKEN_ID, 2, 1),
+    ];
+    setup.stake(&transfers, NO_ERR_MSG);
+    setup.assert_user_score(StakingModuleType::All, 12);
+    setup.assert_user_score(StakingModuleType::Bloodshed, 5);
+    setup.assert_aggregated_score(StakingModuleType::All, 12);
+}
+
+#[test]
+#[allow(deprecated)]
+fn different_token_id_stake_keeps_pending_rewards() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_stake_pool_type(POOL2_TOKEN_ID, StakingModuleType::Bloodshed);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_token_score(StakingModuleType::All, POOL2_TOKEN_ID, 1);
+
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+
+    let transfers = vec![
+        new_nft_transfer(POOL1_TOKEN_ID, 2, 1),
+        new_nft_transfer(POOL2_TOKEN_ID, 1, 1),
     ];
-    setup.stake(&transfers, ERR_ONE_TOKEN_ID_SUPPORTED);
+    setup.stake(&transfers, NO_ERR_MSG);
+    setup.assert_stored_rewards(100_000);
+    setup.assert_pending_reward(100_000);
 }
 
 #[test]
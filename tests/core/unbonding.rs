@@ -1,6 +1,14 @@
+use multiversx_sc::types::ManagedVec;
+use multiversx_sc_scenario::{managed_address, managed_biguint, managed_token_id, rust_biguint};
 use nft_staking::{
-    constants::{DEFAULT_UNBONDING_TIME_PENALTY, ERR_FAILED_UNBONDING, ERR_NOTHING_TO_CLAIM},
+    constants::{
+        DEFAULT_UNBONDING_TIME_PENALTY, ERR_FAILED_UNBONDING, ERR_INVALID_UNBONDING_BATCH,
+        ERR_NOTHING_TO_CLAIM, ERR_NOTHING_TO_UNSTAKE,
+    },
     staking_modules::staking_module_type::StakingModuleType,
+    storage::user_data::UserDataStorageModule,
+    types::{nonce_qty_pair::NonceQtyPair, start_unbonding_payload::StartUnbondingPayload},
+    views::ViewsModule,
 };
 
 use crate::setup::{
@@ -267,7 +275,6 @@ fn unbond_multiple_sft_nonces_includes_quantity() {
     );
 }
 
-
 #[test]
 #[allow(deprecated)]
 fn unbond_same_nonce_multiple_times_more_than_staked_fails() {
@@ -277,7 +284,11 @@ fn unbond_same_nonce_multiple_times_more_than_staked_fails() {
     setup.set_token_score(StakingModuleType::CodingDivisionSfts, POOL2_TOKEN_ID, 1);
     setup.stake(&transfers, NO_ERR_MSG);
 
-    setup.start_unbonding(POOL2_TOKEN_ID, &[(1, 1), (1, 1), (1, 1)], ERR_FAILED_UNBONDING);
+    setup.start_unbonding(
+        POOL2_TOKEN_ID,
+        &[(1, 1), (1, 1), (1, 1)],
+        ERR_FAILED_UNBONDING,
+    );
 }
 
 #[test]
@@ -289,8 +300,313 @@ fn double_claim_unbonded_fails_with_expected_error() {
     setup.stake(&transfers, NO_ERR_MSG);
 
     setup.start_unbonding(POOL1_TOKEN_ID, &[(1, 1)], NO_ERR_MSG);
-    setup.b_mock.set_block_timestamp(DEFAULT_UNBONDING_TIME_PENALTY + 1);
+    setup
+        .b_mock
+        .set_block_timestamp(DEFAULT_UNBONDING_TIME_PENALTY + 1);
+
+    setup.claim_unbonded(NO_ERR_MSG);
+    setup.claim_unbonded(ERR_NOTHING_TO_CLAIM);
+}
+
+#[test]
+#[allow(deprecated)]
+fn same_block_unbondings_are_claimed_separately() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfers = vec![
+        new_nft_transfer(POOL1_TOKEN_ID, 1, 1),
+        new_nft_transfer(POOL1_TOKEN_ID, 2, 1),
+    ];
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(1, 1)], NO_ERR_MSG);
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(2, 1)], NO_ERR_MSG);
+    setup
+        .b_mock
+        .set_block_timestamp(DEFAULT_UNBONDING_TIME_PENALTY);
+
+    setup.claim_unbonded(NO_ERR_MSG);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 1, 1);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 2, 1);
+}
+
+#[test]
+#[allow(deprecated)]
+fn unbonding_batches_listed_in_user_staking_data() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfers = vec![
+        new_nft_transfer(POOL1_TOKEN_ID, 1, 1),
+        new_nft_transfer(POOL1_TOKEN_ID, 2, 1),
+    ];
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(1, 1)], NO_ERR_MSG);
+    setup.b_mock.set_block_timestamp(10);
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(2, 1)], NO_ERR_MSG);
+
+    let user_address = setup.user_address.clone();
+    setup
+        .b_mock
+        .execute_query(&setup.contract_wrapper, |sc| {
+            let user_data = sc.get_user_staking_data(managed_address!(&user_address));
+            assert_eq!(user_data.unbonding_assets.len(), 2);
+
+            let first_batch = user_data.unbonding_assets.get(0);
+            assert_eq!(first_batch.batch_id, 1);
+            assert_eq!(first_batch.start_timestamp, 0);
+            assert_eq!(
+                first_batch.release_timestamp,
+                DEFAULT_UNBONDING_TIME_PENALTY
+            );
+
+            let second_batch = user_data.unbonding_assets.get(1);
+            assert_eq!(second_batch.batch_id, 2);
+            assert_eq!(second_batch.start_timestamp, 10);
+            assert_eq!(
+                second_batch.release_timestamp,
+                10 + DEFAULT_UNBONDING_TIME_PENALTY
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+#[allow(deprecated)]
+fn legacy_unbonding_assets_successful_claim() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    // simulate a batch created before unbonding batch ids were introduced
+    let user_address = setup.user_address.clone();
+    setup
+        .b_mock
+        .execute_tx(
+            &setup.owner_address,
+            &setup.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let user = managed_address!(&user_address);
+                let token_id = managed_token_id!(POOL1_TOKEN_ID);
+                sc.staked_nfts(&user, &token_id).remove(&1);
+
+                let mut items = ManagedVec::new();
+                items.push(NonceQtyPair {
+                    nonce: 1,
+                    quantity: managed_biguint!(1),
+                });
+                sc.unbonding_assets(&user)
+                    .insert(5, StartUnbondingPayload::new(token_id, items));
+            },
+        )
+        .assert_ok();
+
+    setup
+        .b_mock
+        .set_block_timestamp(DEFAULT_UNBONDING_TIME_PENALTY + 4);
+    setup.claim_unbonded(ERR_NOTHING_TO_CLAIM);
 
+    setup
+        .b_mock
+        .set_block_timestamp(DEFAULT_UNBONDING_TIME_PENALTY + 5);
     setup.claim_unbonded(NO_ERR_MSG);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 1, 1);
+}
+
+#[test]
+#[allow(deprecated)]
+fn cancel_unbonding_restakes_batch() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfers = vec![
+        new_nft_transfer(POOL1_TOKEN_ID, 1, 1),
+        new_nft_transfer(POOL1_TOKEN_ID, 2, 1),
+    ];
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_token_score(StakingModuleType::XBunnies, POOL1_TOKEN_ID, 2);
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(1, 1), (2, 1)], NO_ERR_MSG);
+    setup.assert_user_score(StakingModuleType::All, 0);
+    setup.assert_user_score(StakingModuleType::XBunnies, 0);
+
+    setup.cancel_unbonding(1, NO_ERR_MSG);
+    setup.assert_user_score(StakingModuleType::All, 2);
+    setup.assert_user_score(StakingModuleType::XBunnies, 4);
+    setup.assert_aggregated_score(StakingModuleType::All, 2);
+    setup.assert_aggregated_score(StakingModuleType::XBunnies, 4);
+
+    setup
+        .b_mock
+        .set_block_timestamp(DEFAULT_UNBONDING_TIME_PENALTY + 1);
     setup.claim_unbonded(ERR_NOTHING_TO_CLAIM);
-}
\ No newline at end of file
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(1, 1), (2, 1)], NO_ERR_MSG);
+}
+
+#[test]
+#[allow(deprecated)]
+fn cancel_unbonding_keeps_other_batches() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfers = vec![
+        new_nft_transfer(POOL1_TOKEN_ID, 1, 1),
+        new_nft_transfer(POOL1_TOKEN_ID, 2, 1),
+    ];
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(1, 1)], NO_ERR_MSG);
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(2, 1)], NO_ERR_MSG);
+    setup.cancel_unbonding(2, NO_ERR_MSG);
+
+    setup
+        .b_mock
+        .set_block_timestamp(DEFAULT_UNBONDING_TIME_PENALTY + 1);
+    setup.claim_unbonded(NO_ERR_MSG);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 1, 1);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 2, 0);
+}
+
+#[test]
+fn cancel_unknown_unbonding_batch_fails() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(1, 1)], NO_ERR_MSG);
+    setup.cancel_unbonding(2, ERR_INVALID_UNBONDING_BATCH);
+    setup.cancel_unbonding(1, NO_ERR_MSG);
+    setup.cancel_unbonding(1, ERR_INVALID_UNBONDING_BATCH);
+}
+
+#[test]
+#[allow(deprecated)]
+fn pool_unbonding_time_penalty_overrides_global_value() {
+    let pool_unbonding_time_penalty = 100;
+
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfers_1 = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    let transfers_2 = vec![new_nft_transfer(POOL2_TOKEN_ID, 1, 1)];
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_stake_pool_type(POOL2_TOKEN_ID, StakingModuleType::Bloodshed);
+    setup.set_pool_unbonding_time_penalty(POOL1_TOKEN_ID, pool_unbonding_time_penalty);
+    setup.stake(&transfers_1, NO_ERR_MSG);
+    setup.stake(&transfers_2, NO_ERR_MSG);
+
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(1, 1)], NO_ERR_MSG);
+    setup.start_unbonding(POOL2_TOKEN_ID, &[(1, 1)], NO_ERR_MSG);
+
+    setup
+        .b_mock
+        .set_block_timestamp(pool_unbonding_time_penalty);
+    setup.claim_unbonded(NO_ERR_MSG);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 1, 1);
+    setup.assert_user_nft_balance(POOL2_TOKEN_ID, 1, POOL2_QUANTITY_PER_NONCE as u64 - 1);
+
+    setup
+        .b_mock
+        .set_block_timestamp(DEFAULT_UNBONDING_TIME_PENALTY);
+    setup.claim_unbonded(NO_ERR_MSG);
+    setup.assert_user_nft_balance(POOL2_TOKEN_ID, 1, POOL2_QUANTITY_PER_NONCE as u64);
+}
+
+#[test]
+#[allow(deprecated)]
+fn pool_unbonding_time_penalty_change_keeps_pending_batches() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    let transfers = vec![new_nft_transfer(POOL1_TOKEN_ID, 1, 1)];
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_pool_unbonding_time_penalty(POOL1_TOKEN_ID, 100);
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    setup.start_unbonding(POOL1_TOKEN_ID, &[(1, 1)], NO_ERR_MSG);
+    setup.set_pool_unbonding_time_penalty(POOL1_TOKEN_ID, 1_000);
+
+    setup.b_mock.set_block_timestamp(100);
+    setup.claim_unbonded(NO_ERR_MSG);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 1, 1);
+}
+
+#[test]
+#[allow(deprecated)]
+fn multiple_collections_successful_unbonding() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_stake_pool_type(POOL2_TOKEN_ID, StakingModuleType::Bloodshed);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_token_score(StakingModuleType::All, POOL2_TOKEN_ID, 1);
+    setup.set_token_score(StakingModuleType::Bloodshed, POOL2_TOKEN_ID, 3);
+
+    let transfers = vec![
+        new_nft_transfer(POOL1_TOKEN_ID, 1, 1),
+        new_nft_transfer(POOL1_TOKEN_ID, 2, 1),
+        new_nft_transfer(POOL2_TOKEN_ID, 1, 1),
+    ];
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    setup.start_unbonding_multiple(
+        &[(POOL1_TOKEN_ID, &[(1, 1)]), (POOL2_TOKEN_ID, &[(1, 1)])],
+        NO_ERR_MSG,
+    );
+    setup.assert_user_score(StakingModuleType::All, 1);
+    setup.assert_user_score(StakingModuleType::Bloodshed, 0);
+    setup.assert_aggregated_score(StakingModuleType::All, 1);
+
+    setup
+        .b_mock
+        .set_block_timestamp(DEFAULT_UNBONDING_TIME_PENALTY + 1);
+    setup.claim_unbonded(NO_ERR_MSG);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 1, 1);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 2, 0);
+    setup.assert_user_nft_balance(POOL2_TOKEN_ID, 1, POOL2_QUANTITY_PER_NONCE as u64);
+}
+
+#[test]
+fn multiple_collections_unbonding_fails_if_any_payload_is_invalid() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_stake_pool_type(POOL2_TOKEN_ID, StakingModuleType::Bloodshed);
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+
+    setup.start_unbonding_multiple(
+        &[(POOL1_TOKEN_ID, &[(1, 1)]), (POOL2_TOKEN_ID, &[(1, 1)])],
+        ERR_FAILED_UNBONDING,
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn unstake_all_unbonds_every_pool() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_stake_pool_type(POOL2_TOKEN_ID, StakingModuleType::Bloodshed);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_token_score(StakingModuleType::XBunnies, POOL1_TOKEN_ID, 2);
+    setup.set_token_score(StakingModuleType::Bloodshed, POOL2_TOKEN_ID, 3);
+
+    let transfers = vec![
+        new_nft_transfer(POOL1_TOKEN_ID, 1, 1),
+        new_nft_transfer(POOL1_TOKEN_ID, 2, 1),
+        new_nft_transfer(POOL2_TOKEN_ID, 1, 1),
+    ];
+    setup.stake(&transfers, NO_ERR_MSG);
+
+    setup.unstake_all(NO_ERR_MSG);
+    setup.assert_user_score(StakingModuleType::All, 0);
+    setup.assert_user_score(StakingModuleType::XBunnies, 0);
+    setup.assert_user_score(StakingModuleType::Bloodshed, 0);
+    setup.assert_aggregated_score(StakingModuleType::All, 0);
+    setup.assert_aggregated_score(StakingModuleType::Bloodshed, 0);
+
+    setup
+        .b_mock
+        .set_block_timestamp(DEFAULT_UNBONDING_TIME_PENALTY + 1);
+    setup.claim_unbonded(NO_ERR_MSG);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 1, 1);
+    setup.assert_user_nft_balance(POOL1_TOKEN_ID, 2, 1);
+    setup.assert_user_nft_balance(POOL2_TOKEN_ID, 1, POOL2_QUANTITY_PER_NONCE as u64);
+
+    setup.unstake_all(ERR_NOTHING_TO_UNSTAKE);
+}
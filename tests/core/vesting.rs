@@ -0,0 +1,124 @@
+use multiversx_sc_scenario::{managed_token_id_wrapped, DebugApi};
+use nft_staking::{
+    constants::{ERR_EARLY_WITHDRAW_DISABLED, ERR_INVALID_VESTING_CONFIG, ERR_NOTHING_TO_CLAIM},
+    staking_modules::staking_module_type::StakingModuleType,
+    storage::user_data::UserDataStorageModule,
+};
+
+use crate::setup::{
+    constants::{NO_ERR_MSG, POOL1_TOKEN_ID, REWARD_TOKEN_ID},
+    types::new_nft_transfer,
+    ContractSetup,
+};
+
+#[allow(deprecated)]
+fn setup_claimed_vesting_rewards<ContractObjBuilder>(
+    builder: ContractObjBuilder,
+    early_withdraw_penalty: u64,
+) -> ContractSetup<ContractObjBuilder>
+where
+    ContractObjBuilder: 'static + Copy + Fn() -> nft_staking::ContractObj<DebugApi>,
+{
+    let mut setup = ContractSetup::new(builder);
+    setup.set_token_score(StakingModuleType::All, POOL1_TOKEN_ID, 1);
+    setup.set_stake_pool_type(POOL1_TOKEN_ID, StakingModuleType::XBunnies);
+    setup.set_vesting_config(
+        REWARD_TOKEN_ID,
+        10,
+        early_withdraw_penalty,
+        StakingModuleType::All,
+        NO_ERR_MSG,
+    );
+    setup.stake(&[new_nft_transfer(POOL1_TOKEN_ID, 1, 1)], NO_ERR_MSG);
+
+    setup.b_mock.set_block_epoch(2);
+    setup.distribute_reward(100_000, NO_ERR_MSG);
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 0);
+
+    setup
+}
+
+#[test]
+#[allow(deprecated)]
+fn claimed_rewards_vest_linearly() {
+    let mut setup = setup_claimed_vesting_rewards(nft_staking::contract_obj, 0);
+    setup.withdraw_vested(ERR_NOTHING_TO_CLAIM);
+
+    setup.b_mock.set_block_epoch(5);
+    setup.withdraw_vested(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 30_000);
+
+    setup.b_mock.set_block_epoch(20);
+    setup.withdraw_vested(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 100_000);
+    setup.withdraw_vested(ERR_NOTHING_TO_CLAIM);
+}
+
+#[test]
+#[allow(deprecated)]
+fn early_withdrawal_penalty_goes_to_stakers() {
+    let mut setup = setup_claimed_vesting_rewards(nft_staking::contract_obj, 5_000);
+
+    setup.b_mock.set_block_epoch(4);
+    setup.withdraw_vested_early(REWARD_TOKEN_ID, NO_ERR_MSG);
+    // 20_000 unlocked, half of the remaining 80_000 forfeited
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 60_000);
+    setup.withdraw_vested(ERR_NOTHING_TO_CLAIM);
+
+    setup
+        .b_mock
+        .execute_query(&setup.contract_wrapper, |sc| {
+            let undistributed_rewards = sc
+                .undistributed_rewards(
+                    &StakingModuleType::All,
+                    &managed_token_id_wrapped!(REWARD_TOKEN_ID),
+                    0,
+                )
+                .get();
+            assert_eq!(undistributed_rewards, 40_000u64);
+        })
+        .assert_ok();
+}
+
+#[test]
+fn early_withdrawal_disabled_without_penalty() {
+    let mut setup = setup_claimed_vesting_rewards(nft_staking::contract_obj, 0);
+    setup.withdraw_vested_early(REWARD_TOKEN_ID, ERR_EARLY_WITHDRAW_DISABLED);
+}
+
+#[test]
+fn vesting_config_validation() {
+    let mut setup = ContractSetup::new(nft_staking::contract_obj);
+    setup.set_vesting_config(
+        REWARD_TOKEN_ID,
+        0,
+        0,
+        StakingModuleType::All,
+        ERR_INVALID_VESTING_CONFIG,
+    );
+    setup.set_vesting_config(
+        REWARD_TOKEN_ID,
+        10,
+        10_001,
+        StakingModuleType::All,
+        ERR_INVALID_VESTING_CONFIG,
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn relocking_averages_vesting_end_by_amount() {
+    let mut setup = setup_claimed_vesting_rewards(nft_staking::contract_obj, 0);
+
+    // half of the first claim is still locked for 5 epochs when the same amount is locked for 10
+    setup.b_mock.set_block_epoch(7);
+    setup.distribute_reward(50_000, NO_ERR_MSG);
+    setup.claim_rewards(NO_ERR_MSG);
+    setup.withdraw_vested(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 50_000);
+
+    setup.b_mock.set_block_epoch(15);
+    setup.withdraw_vested(NO_ERR_MSG);
+    setup.assert_user_token_balance(REWARD_TOKEN_ID, 0, 150_000);
+}
@@ -11,3 +11,6 @@ pub const POOL2_QUANTITY_PER_NONCE: u32 = 100;
 pub const REWARD_TOKEN_ID: &[u8] = b"REWARD-123456";
 pub const SECONDARY_REWARD_TOKEN_ID_1: &[u8] = b"SNDREWARD-123456";
 pub const SECONDARY_REWARD_TOKEN_ID_2: &[u8] = b"SNDREWARD2-123456";
+
+pub const SFT_REWARD_TOKEN_ID: &[u8] = b"CONSUMABLE-123456";
+pub const SFT_REWARD_TOKEN_NONCE: u64 = 7;
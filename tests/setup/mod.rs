@@ -1,20 +1,25 @@
-use multiversx_sc::types::{Address, ManagedVec};
+use multiversx_sc::types::{Address, EsdtTokenPayment, ManagedVec, MultiValueEncoded};
 #[allow(deprecated)]
 use multiversx_sc_scenario::whitebox_legacy::{BlockchainStateWrapper, ContractObjWrapper};
-use multiversx_sc_scenario::{managed_address, managed_biguint, managed_token_id, DebugApi};
+use multiversx_sc_scenario::{
+    managed_address, managed_biguint, managed_token_id, managed_token_id_wrapped, DebugApi,
+};
 use multiversx_sc_scenario::{rust_biguint, testing_framework::TxResult};
 use nft_staking::owner::OwnerModule;
+use nft_staking::pause::PauseModule;
 use nft_staking::staking_modules::staking_module_type::StakingModuleType;
-use nft_staking::storage::config::ConfigModule;
 use nft_staking::storage::score::ScoreStorageModule;
 use nft_staking::storage::user_data::UserDataStorageModule;
+use nft_staking::types::instant_unstake_fee::{FeeDestination, InstantUnstakeFee};
 use nft_staking::types::start_unbonding_payload::StartUnbondingPayload;
+use nft_staking::vesting::VestingModule;
+use nft_staking::views::ViewsModule;
 use nft_staking::NftStakingContract;
 
 use self::constants::{
     NONCES_PER_TOKEN, NO_ERR_MSG, POOL1_QUANTITY_PER_NONCE, POOL1_TOKEN_ID,
     POOL2_QUANTITY_PER_NONCE, POOL2_TOKEN_ID, REWARD_TOKEN_ID, SECONDARY_REWARD_TOKEN_ID_1,
-    SECONDARY_REWARD_TOKEN_ID_2,
+    SECONDARY_REWARD_TOKEN_ID_2, SFT_REWARD_TOKEN_ID, SFT_REWARD_TOKEN_NONCE,
 };
 use self::types::{NonceQtyPair, TransferAssetType, TransferAssetTypeParserVec};
 use nft_staking::types::nonce_qty_pair::NonceQtyPair as NonceQtyPairSc;
@@ -56,16 +61,12 @@ where
 
         b_mock
             .execute_tx(&owner_address, &contract_wrapper, &rust_zero, |sc| {
-                sc.init(managed_token_id!(REWARD_TOKEN_ID));
+                sc.init(
+                    managed_token_id_wrapped!(REWARD_TOKEN_ID),
+                    MultiValueEncoded::new(),
+                );
                 sc.base_asset_score(&managed_token_id!(POOL1_TOKEN_ID), &StakingModuleType::All)
                     .set(1);
-                sc.reward_token_identifiers()
-                    .insert(managed_token_id!(SECONDARY_REWARD_TOKEN_ID_1));
-                sc.reward_token_identifiers()
-                    .insert(managed_token_id!(SECONDARY_REWARD_TOKEN_ID_2));
-
-                sc.reward_token_id_mapping()
-                    .insert((managed_token_id!(REWARD_TOKEN_ID), StakingModuleType::All));
             })
             .assert_ok();
 
@@ -81,6 +82,29 @@ where
         }
     }
 
+    /// Another user holding the same assets, staking as them goes through `user_address`.
+    pub fn create_staker(&mut self) -> Address {
+        let address = self.b_mock.create_user_account(&rust_biguint!(0));
+        for i in 1..=NONCES_PER_TOKEN {
+            self.b_mock.set_nft_balance(
+                &address,
+                POOL1_TOKEN_ID,
+                i,
+                &rust_biguint!(POOL1_QUANTITY_PER_NONCE),
+                b"",
+            );
+            self.b_mock.set_nft_balance(
+                &address,
+                POOL2_TOKEN_ID,
+                i,
+                &rust_biguint!(POOL2_QUANTITY_PER_NONCE),
+                b"",
+            );
+        }
+
+        address
+    }
+
     pub fn stake(&mut self, transfers: &[TransferAssetType], err_msg: &str) {
         let parsed_transfers = transfers.to_vec().parse_vec();
         let tx_result = self.b_mock.execute_esdt_multi_transfer(
@@ -105,19 +129,71 @@ where
             &self.contract_wrapper,
             &rust_biguint!(0),
             |sc| {
-                let mut nonce_qty_vec = ManagedVec::new();
-                for nonce_qty_pair in nonce_qty_pairs {
-                    nonce_qty_vec.push(NonceQtyPairSc {
-                        nonce: nonce_qty_pair.0,
-                        quantity: managed_biguint!(nonce_qty_pair.1),
-                    });
+                sc.start_unbonding(new_unbonding_payload(token_id, nonce_qty_pairs));
+            },
+        );
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn start_unbonding_multiple(
+        &mut self,
+        payloads: &[(&[u8], &[NonceQtyPair])],
+        err_msg: &str,
+    ) {
+        let tx_result = self.b_mock.execute_tx(
+            &self.user_address,
+            &self.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut sc_payloads = MultiValueEncoded::new();
+                for (token_id, nonce_qty_pairs) in payloads {
+                    sc_payloads.push(new_unbonding_payload(token_id, nonce_qty_pairs));
                 }
-                let payload = StartUnbondingPayload {
-                    token_identifier: managed_token_id!(token_id),
-                    items: nonce_qty_vec,
-                };
 
-                sc.start_unbonding(payload);
+                sc.start_unbonding_multiple(sc_payloads);
+            },
+        );
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn unstake_all(&mut self, err_msg: &str) {
+        let tx_result = self.b_mock.execute_tx(
+            &self.user_address,
+            &self.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.unstake_all();
+            },
+        );
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn instant_unstake(
+        &mut self,
+        token_id: &[u8],
+        nonce_qty_pairs: &[NonceQtyPair],
+        fee_transfers: &[TransferAssetType],
+        err_msg: &str,
+    ) {
+        let parsed_transfers = fee_transfers.to_vec().parse_vec();
+        let tx_result = self.b_mock.execute_esdt_multi_transfer(
+            &self.user_address,
+            &self.contract_wrapper,
+            &parsed_transfers,
+            |sc| {
+                sc.instant_unstake(new_unbonding_payload(token_id, nonce_qty_pairs));
+            },
+        );
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn cancel_unbonding(&mut self, batch_id: u64, err_msg: &str) {
+        let tx_result = self.b_mock.execute_tx(
+            &self.user_address,
+            &self.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.cancel_unbonding(batch_id);
             },
         );
         Self::assert_tx_result(&tx_result, err_msg);
@@ -225,6 +301,154 @@ where
             .assert_ok();
     }
 
+    pub fn set_pool_unbonding_time_penalty(
+        &mut self,
+        token_id: &[u8],
+        unbonding_time_penalty: u64,
+    ) {
+        self.b_mock
+            .execute_tx(
+                &self.owner_address,
+                &self.contract_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    sc.set_pool_unbonding_time_penalty(
+                        managed_token_id!(token_id),
+                        unbonding_time_penalty,
+                    );
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn set_treasury_address(&mut self, treasury_address: &Address) {
+        self.b_mock
+            .execute_tx(
+                &self.owner_address,
+                &self.contract_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    sc.set_treasury_address(managed_address!(treasury_address));
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn set_instant_unstake_payment_fee(
+        &mut self,
+        token_id: &[u8],
+        fee_token_id: &[u8],
+        fee_amount: u64,
+        destination: FeeDestination,
+    ) {
+        self.b_mock
+            .execute_tx(
+                &self.owner_address,
+                &self.contract_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    let fee = InstantUnstakeFee::Payment(EsdtTokenPayment::new(
+                        managed_token_id!(fee_token_id),
+                        0,
+                        managed_biguint!(fee_amount),
+                    ));
+                    sc.set_instant_unstake_fee(managed_token_id!(token_id), fee, destination);
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn add_pauser(&mut self, pauser_address: &Address) {
+        self.b_mock
+            .execute_tx(
+                &self.owner_address,
+                &self.contract_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    sc.add_pauser(managed_address!(pauser_address));
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn set_claim_rewards_pausable(&mut self, pausable: bool) {
+        self.b_mock
+            .execute_tx(
+                &self.owner_address,
+                &self.contract_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    sc.set_claim_rewards_pausable(pausable);
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn force_unstake(&mut self, token_id: &[u8], err_msg: &str) {
+        let user_address = self.user_address.clone();
+        let tx_result = self.b_mock.execute_tx(
+            &self.owner_address,
+            &self.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.force_unstake(managed_address!(&user_address), managed_token_id!(token_id));
+            },
+        );
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn set_emergency_mode(&mut self, enabled: bool) {
+        self.b_mock
+            .execute_tx(
+                &self.owner_address,
+                &self.contract_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    sc.set_emergency_mode(enabled);
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn emergency_withdraw(&mut self, err_msg: &str) {
+        let tx_result = self.b_mock.execute_tx(
+            &self.user_address,
+            &self.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.emergency_withdraw();
+            },
+        );
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn pause(&mut self, caller: &Address, err_msg: &str) {
+        let tx_result =
+            self.b_mock
+                .execute_tx(caller, &self.contract_wrapper, &rust_biguint!(0), |sc| {
+                    sc.pause();
+                });
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn unpause(&mut self, caller: &Address, err_msg: &str) {
+        let tx_result =
+            self.b_mock
+                .execute_tx(caller, &self.contract_wrapper, &rust_biguint!(0), |sc| {
+                    sc.unpause();
+                });
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn pause_pool(&mut self, caller: &Address, token_id: &[u8], err_msg: &str) {
+        let tx_result =
+            self.b_mock
+                .execute_tx(caller, &self.contract_wrapper, &rust_biguint!(0), |sc| {
+                    sc.pause_pool(managed_token_id!(token_id));
+                });
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
     pub fn register_token_as_eligible_reward_for_pool(
         &mut self,
         reward_token_id: &[u8],
@@ -236,13 +460,74 @@ where
                 &self.contract_wrapper,
                 &rust_biguint!(0),
                 |sc| {
-                    sc.reward_token_id_mapping()
-                        .insert((managed_token_id!(reward_token_id), pool_type));
+                    sc.register_reward_token(
+                        managed_token_id_wrapped!(reward_token_id),
+                        pool_type,
+                        None.into(),
+                    );
                 },
             )
             .assert_ok();
     }
 
+    pub fn register_reward_token(
+        &mut self,
+        reward_token_id: &[u8],
+        pool_type: StakingModuleType,
+        reward_token_nonce: Option<u64>,
+        err_msg: &str,
+    ) {
+        let tx_result = self.b_mock.execute_tx(
+            &self.owner_address,
+            &self.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.register_reward_token(
+                    managed_token_id_wrapped!(reward_token_id),
+                    pool_type,
+                    reward_token_nonce.into(),
+                );
+            },
+        );
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn retire_reward_token(
+        &mut self,
+        reward_token_id: &[u8],
+        sweep_deadline: u64,
+        err_msg: &str,
+    ) {
+        let tx_result = self.b_mock.execute_tx(
+            &self.owner_address,
+            &self.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.retire_reward_token(
+                    managed_token_id_wrapped!(reward_token_id),
+                    sweep_deadline,
+                    None.into(),
+                );
+            },
+        );
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn sweep_retired_reward_token(&mut self, reward_token_id: &[u8], err_msg: &str) {
+        let tx_result = self.b_mock.execute_tx(
+            &self.owner_address,
+            &self.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.sweep_retired_reward_token(
+                    managed_token_id_wrapped!(reward_token_id),
+                    None.into(),
+                );
+            },
+        );
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
     pub fn distribute_reward(&mut self, amount: u64, err_msg: &str) {
         let tx_result = self.b_mock.execute_esdt_transfer(
             &self.owner_address,
@@ -257,6 +542,38 @@ where
         Self::assert_tx_result(&tx_result, err_msg);
     }
 
+    pub fn distribute_sft_reward(
+        &mut self,
+        token_id: &[u8],
+        token_nonce: u64,
+        amount: u64,
+        err_msg: &str,
+    ) {
+        let tx_result = self.b_mock.execute_esdt_transfer(
+            &self.owner_address,
+            &self.contract_wrapper,
+            token_id,
+            token_nonce,
+            &rust_biguint!(amount),
+            |sc| {
+                sc.distribute_reward();
+            },
+        );
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn distribute_egld_reward(&mut self, amount: u64, err_msg: &str) {
+        let tx_result = self.b_mock.execute_tx(
+            &self.owner_address,
+            &self.contract_wrapper,
+            &rust_biguint!(amount),
+            |sc| {
+                sc.distribute_reward();
+            },
+        );
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
     pub fn distribute_secondary_reward(
         &mut self,
         token_id: &[u8],
@@ -277,6 +594,45 @@ where
         Self::assert_tx_result(&tx_result, err_msg);
     }
 
+    pub fn set_distribution_weights(
+        &mut self,
+        token_id: &[u8],
+        weights: &[(StakingModuleType, u64)],
+        err_msg: &str,
+    ) {
+        let tx_result = self.b_mock.execute_tx(
+            &self.owner_address,
+            &self.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut managed_weights = MultiValueEncoded::new();
+                for (staking_module, weight) in weights.iter() {
+                    managed_weights.push((staking_module.clone(), *weight).into());
+                }
+                sc.set_distribution_weights(
+                    managed_token_id_wrapped!(token_id),
+                    0,
+                    managed_weights,
+                );
+            },
+        );
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn distribute_weighted(&mut self, token_id: &[u8], amount: u64, err_msg: &str) {
+        let tx_result = self.b_mock.execute_esdt_transfer(
+            &self.owner_address,
+            &self.contract_wrapper,
+            token_id,
+            0,
+            &rust_biguint!(amount),
+            |sc| {
+                sc.distribute_weighted();
+            },
+        );
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
     pub fn assert_pending_reward(&mut self, expected_amount: u64) {
         let address = self.user_address.clone();
         self.b_mock
@@ -334,13 +690,198 @@ where
         self.b_mock
             .execute_query(&self.contract_wrapper, |sc| {
                 let reward_rate = sc
-                    .reward_rate(epoch, &staking_module, &managed_token_id!(token_id))
+                    .reward_rate(epoch, &staking_module, &managed_token_id_wrapped!(token_id))
                     .get();
                 assert_eq!(managed_biguint!(expected_amount), reward_rate);
             })
             .assert_ok();
     }
 
+    /// Turns the user's `StakingModuleType::All` position into one opened under the per-epoch
+    /// model, last claimed in `last_claimed_epoch` and earning `reward_rate` per score until the
+    /// upgrade in `legacy_reward_epoch_end`.
+    pub fn set_legacy_reward_position(
+        &mut self,
+        last_claimed_epoch: u64,
+        legacy_reward_epoch_end: u64,
+        reward_rate: u64,
+    ) {
+        let user_address = self.user_address.clone();
+        self.b_mock
+            .execute_tx(
+                &self.owner_address,
+                &self.contract_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    let user = managed_address!(&user_address);
+                    let token_id = managed_token_id_wrapped!(REWARD_TOKEN_ID);
+                    sc.last_claimed_epoch(&StakingModuleType::All, &user)
+                        .set(last_claimed_epoch);
+                    for epoch in 1..=legacy_reward_epoch_end {
+                        sc.reward_rate(epoch, &StakingModuleType::All, &token_id)
+                            .set(managed_biguint!(reward_rate));
+                    }
+                    sc.legacy_reward_epoch_end().set(legacy_reward_epoch_end);
+                    sc.legacy_reward_tokens().insert(token_id);
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn settle_legacy_rewards(&mut self, max_epochs: u64, err_msg: &str) {
+        let tx_result = self.b_mock.execute_tx(
+            &self.user_address,
+            &self.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.settle_legacy_rewards(max_epochs);
+            },
+        );
+
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn migrate_reward_checkpoints(&mut self, max_epochs: u64, users: &[Address]) {
+        self.b_mock
+            .execute_tx(
+                &self.owner_address,
+                &self.contract_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    let mut managed_users = MultiValueEncoded::new();
+                    for user in users.iter() {
+                        managed_users.push(managed_address!(user));
+                    }
+                    sc.migrate_reward_checkpoints(max_epochs, managed_users);
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn fund_reward_schedule(
+        &mut self,
+        staking_module: StakingModuleType,
+        token_id: &[u8],
+        amount: u64,
+        epochs: u64,
+        err_msg: &str,
+    ) {
+        let tx_result = self.b_mock.execute_esdt_transfer(
+            &self.owner_address,
+            &self.contract_wrapper,
+            token_id,
+            0,
+            &rust_biguint!(amount),
+            |sc| {
+                sc.fund_reward_schedule(staking_module, epochs);
+            },
+        );
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn deposit_reward_reservoir(
+        &mut self,
+        staking_module: StakingModuleType,
+        token_id: &[u8],
+        amount: u64,
+        distribution_amount: u64,
+    ) {
+        self.b_mock
+            .execute_esdt_transfer(
+                &self.owner_address,
+                &self.contract_wrapper,
+                token_id,
+                0,
+                &rust_biguint!(amount),
+                |sc| {
+                    sc.deposit_reward_reservoir(staking_module.clone());
+                    sc.set_reservoir_distribution_amount(
+                        staking_module,
+                        managed_token_id_wrapped!(token_id),
+                        managed_biguint!(distribution_amount),
+                        None.into(),
+                    );
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn trigger_distribution(
+        &mut self,
+        staking_module: StakingModuleType,
+        token_id: &[u8],
+        err_msg: &str,
+    ) {
+        let tx_result = self.b_mock.execute_tx(
+            &self.user_address,
+            &self.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.trigger_distribution(
+                    staking_module,
+                    managed_token_id_wrapped!(token_id),
+                    None.into(),
+                );
+            },
+        );
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn recover_zero_score_reserve(
+        &mut self,
+        staking_module: StakingModuleType,
+        token_id: &[u8],
+        err_msg: &str,
+    ) {
+        let tx_result = self.b_mock.execute_tx(
+            &self.owner_address,
+            &self.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.recover_zero_score_reserve(
+                    staking_module,
+                    managed_token_id_wrapped!(token_id),
+                    None.into(),
+                );
+            },
+        );
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn assert_reward_schedule_remaining_epochs(
+        &mut self,
+        staking_module: StakingModuleType,
+        token_id: &[u8],
+        expected_epochs: u64,
+    ) {
+        self.b_mock
+            .execute_query(&self.contract_wrapper, |sc| {
+                let remaining_epochs = sc.get_reward_schedule_remaining_epochs(
+                    staking_module,
+                    managed_token_id_wrapped!(token_id),
+                    None.into(),
+                );
+                assert_eq!(expected_epochs, remaining_epochs);
+            })
+            .assert_ok();
+    }
+
+    pub fn assert_reward_dust(
+        &mut self,
+        staking_module: StakingModuleType,
+        token_id: &[u8],
+        expected_amount: u64,
+    ) {
+        self.b_mock
+            .execute_query(&self.contract_wrapper, |sc| {
+                let reward_dust = sc
+                    .reward_dust(&staking_module, &managed_token_id_wrapped!(token_id), 0)
+                    .get();
+                assert_eq!(managed_biguint!(expected_amount), reward_dust);
+            })
+            .assert_ok();
+    }
+
     pub fn claim_rewards(&mut self, err_msg: &str) {
         let tx_result = self.b_mock.execute_tx(
             &self.user_address,
@@ -354,6 +895,114 @@ where
         Self::assert_tx_result(&tx_result, err_msg);
     }
 
+    pub fn claim_rewards_for_tokens(&mut self, token_ids: &[&[u8]], err_msg: &str) {
+        let tx_result = self.b_mock.execute_tx(
+            &self.user_address,
+            &self.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                let mut managed_token_ids = MultiValueEncoded::new();
+                for token_id in token_ids.iter() {
+                    managed_token_ids.push(managed_token_id_wrapped!(*token_id));
+                }
+                sc.claim_rewards_for_tokens(managed_token_ids);
+            },
+        );
+
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn claim_rewards_to(&mut self, receiver: &Address, err_msg: &str) {
+        let tx_result = self.b_mock.execute_tx(
+            &self.user_address,
+            &self.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.claim_rewards_to(managed_address!(receiver));
+            },
+        );
+
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn set_reward_receiver(&mut self, receiver: &Address) {
+        self.b_mock
+            .execute_tx(
+                &self.user_address,
+                &self.contract_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    sc.set_reward_receiver(managed_address!(receiver));
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn clear_reward_receiver(&mut self) {
+        self.b_mock
+            .execute_tx(
+                &self.user_address,
+                &self.contract_wrapper,
+                &rust_biguint!(0),
+                |sc| {
+                    sc.clear_reward_receiver();
+                },
+            )
+            .assert_ok();
+    }
+
+    pub fn set_vesting_config(
+        &mut self,
+        token_id: &[u8],
+        vesting_epochs: u64,
+        early_withdraw_penalty: u64,
+        penalty_staking_module: StakingModuleType,
+        err_msg: &str,
+    ) {
+        let tx_result = self.b_mock.execute_tx(
+            &self.owner_address,
+            &self.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.set_vesting_config(
+                    managed_token_id_wrapped!(token_id),
+                    vesting_epochs,
+                    early_withdraw_penalty,
+                    penalty_staking_module,
+                    None.into(),
+                );
+            },
+        );
+
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn withdraw_vested(&mut self, err_msg: &str) {
+        let tx_result = self.b_mock.execute_tx(
+            &self.user_address,
+            &self.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.withdraw_vested(MultiValueEncoded::new());
+            },
+        );
+
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
+    pub fn withdraw_vested_early(&mut self, token_id: &[u8], err_msg: &str) {
+        let tx_result = self.b_mock.execute_tx(
+            &self.user_address,
+            &self.contract_wrapper,
+            &rust_biguint!(0),
+            |sc| {
+                sc.withdraw_vested_early(managed_token_id_wrapped!(token_id), None.into());
+            },
+        );
+
+        Self::assert_tx_result(&tx_result, err_msg);
+    }
+
     pub fn assert_user_token_balance(
         &mut self,
         token_id: &[u8],
@@ -402,7 +1051,8 @@ where
                 let stored_pending_rewards = sc
                     .pending_rewards(
                         &managed_address!(&address),
-                        &managed_token_id!(REWARD_TOKEN_ID),
+                        &managed_token_id_wrapped!(REWARD_TOKEN_ID),
+                        0,
                     )
                     .get();
                 assert_eq!(&managed_biguint!(expected_amount), &stored_pending_rewards);
@@ -449,6 +1099,13 @@ where
             SECONDARY_REWARD_TOKEN_ID_2,
             &rust_biguint!(1_000_000_000),
         );
+        b_mock.set_nft_balance(
+            owner_address,
+            SFT_REWARD_TOKEN_ID,
+            SFT_REWARD_TOKEN_NONCE,
+            &rust_biguint!(1_000),
+            b"",
+        );
     }
 
     fn assert_tx_result(tx_result: &TxResult, err_msg: &str) {
@@ -459,3 +1116,21 @@ where
         tx_result.assert_user_error(err_msg);
     }
 }
+
+fn new_unbonding_payload(
+    token_id: &[u8],
+    nonce_qty_pairs: &[NonceQtyPair],
+) -> StartUnbondingPayload<DebugApi> {
+    let mut nonce_qty_vec = ManagedVec::new();
+    for nonce_qty_pair in nonce_qty_pairs {
+        nonce_qty_vec.push(NonceQtyPairSc {
+            nonce: nonce_qty_pair.0,
+            quantity: managed_biguint!(nonce_qty_pair.1),
+        });
+    }
+
+    StartUnbondingPayload {
+        token_identifier: managed_token_id!(token_id),
+        items: nonce_qty_vec,
+    }
+}